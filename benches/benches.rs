@@ -49,7 +49,9 @@ mod day3 {
     use criterion::Criterion;
 
     pub fn bench_p1(c: &mut Criterion) {
-        c.bench_function("d3 p1", |b| b.iter(|| assert_eq!(nthspiral(265_149), 438)));
+        c.bench_function("d3 p1", |b| {
+            b.iter(|| assert_eq!(nthspiral(265_149).unwrap(), 438))
+        });
     }
 
     pub fn bench_p2(c: &mut Criterion) {
@@ -108,9 +110,9 @@ mod day6 {
     const FULL: &str = include_str!("../data/d6-test");
 
     pub fn bench_both(c: &mut Criterion) {
-        let mut input = parse_memory(FULL).unwrap();
+        let input = parse_memory(FULL).unwrap();
         c.bench_function("d6 both", move |b| {
-            b.iter(|| assert_eq!(redistribute(&mut input), (12_841, 8038)))
+            b.iter(|| assert_eq!(redistribute(&input), (12_841, 8038)))
         });
     }
 }
@@ -179,7 +181,7 @@ mod day11 {
 
     pub fn bench_both(c: &mut Criterion) {
         c.bench_function("d11 both", |b| {
-            b.iter(|| assert_eq!(hexgrid(FULL), (824, 1548)))
+            b.iter(|| assert_eq!(hexgrid(FULL).unwrap(), (824, 1548)))
         });
     }
 }
@@ -193,7 +195,7 @@ mod day12 {
     pub fn bench_both(c: &mut Criterion) {
         c.bench_function("d12 both", |b| {
             let graph = parse_graph(FULL).unwrap();
-            b.iter(|| assert_eq!(process_pipegraph(graph.clone()), (128, 209)))
+            b.iter(|| assert_eq!(process_pipegraph(&graph), (128, 209)))
         });
     }
 }
@@ -214,7 +216,7 @@ mod day13 {
     pub fn bench_p2(c: &mut Criterion) {
         c.bench_function("d13 p2", |b| {
             let layers = parse_layers(FULL).unwrap();
-            b.iter(|| assert_eq!(delay(&layers), 3_905_748))
+            b.iter(|| assert_eq!(delay(&layers), Some(3_905_748)))
         });
     }
 }
@@ -234,7 +236,7 @@ mod day14 {
     pub fn bench_p2(c: &mut Criterion) {
         c.bench_function("d14 p2", |b| {
             let grid = parse_grid(FULL);
-            b.iter(|| assert_eq!(regions(grid.clone()), 1164))
+            b.iter(|| assert_eq!(regions(&grid), 1164))
         });
     }
 }
@@ -244,11 +246,11 @@ mod day15 {
     use criterion::Criterion;
 
     pub fn bench_p1(c: &mut Criterion) {
-        c.bench_function("d15 p1", |b| b.iter(|| assert_eq!(first(634, 301), 573)));
+        c.bench_function("d15 p1", |b| b.iter(|| assert_eq!(first(634, 301, 40_000_000), 573)));
     }
 
     pub fn bench_p2(c: &mut Criterion) {
-        c.bench_function("d15 p2", |b| b.iter(|| assert_eq!(second(634, 301), 294)));
+        c.bench_function("d15 p2", |b| b.iter(|| assert_eq!(second(634, 301, 5_000_000), 294)));
     }
 }
 
@@ -278,7 +280,7 @@ mod day17 {
     pub fn bench_p1(c: &mut Criterion) {
         let input = 354;
         c.bench_function("d17 p1", move |b| {
-            b.iter(|| assert_eq!(spinlock(input), 2000))
+            b.iter(|| assert_eq!(spinlock(input, 2017), 2000))
         });
     }
 
@@ -373,14 +375,14 @@ mod day22 {
     pub fn bench_p1(c: &mut Criterion) {
         c.bench_function("d22 p1", |b| {
             let grid = parse_grid(FULL).unwrap();
-            b.iter(|| assert_eq!(infection(grid.clone(), 10_000), 5433))
+            b.iter(|| assert_eq!(infection(grid.clone(), 10_000).unwrap(), 5433))
         });
     }
 
     pub fn bench_p2(c: &mut Criterion) {
         c.bench_function("d22 p2", |b| {
             let grid = parse_grid(FULL).unwrap();
-            b.iter(|| assert_eq!(evolved_infection(grid.clone(), 10_000_000), 2_512_599))
+            b.iter(|| assert_eq!(evolved_infection(grid.clone(), 10_000_000).unwrap(), 2_512_599))
         });
     }
 }
@@ -412,15 +414,15 @@ mod day24 {
 
     pub fn bench_p1(c: &mut Criterion) {
         c.bench_function("d24 p1", |b| {
-            let mut connectors = parse_connectors(FULL).unwrap();
-            b.iter(|| assert_eq!(strongest_bridge(&mut connectors), 1906))
+            let connectors = parse_connectors(FULL).unwrap();
+            b.iter(|| assert_eq!(strongest_bridge(&connectors), 1906))
         });
     }
 
     pub fn bench_p2(c: &mut Criterion) {
         c.bench_function("d24 p2", |b| {
-            let mut connectors = parse_connectors(FULL).unwrap();
-            b.iter(|| assert_eq!(longest_bridge(&mut connectors), 1824))
+            let connectors = parse_connectors(FULL).unwrap();
+            b.iter(|| assert_eq!(longest_bridge(&connectors), 1824))
         });
     }
 }
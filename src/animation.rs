@@ -0,0 +1,511 @@
+//! Animated GIFs of the days that simulate something step by step.
+//!
+//! A `Recorder` is handed every step of a simulation and keeps one frame out
+//! of every few, each drawn as coloured points. The GIF is encoded by hand,
+//! with a single palette shared by all frames.
+
+use std::collections::hash_map::Entry;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use failure::Error;
+use fnv::FnvHashMap;
+
+use geom::Point;
+use image::{Image, Rgb};
+use params::Overrides;
+use {missing, Result};
+
+/// GIF codes never grow beyond this many bits.
+const MAX_CODE_BITS: u8 = 12;
+
+/// The part of the plane that ends up in the frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Viewport {
+    /// Top left corner
+    pub origin: Point,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Viewport {
+    pub fn new(origin: Point, width: usize, height: usize) -> Viewport {
+        Viewport {
+            origin,
+            width,
+            height,
+        }
+    }
+
+    /// The smallest viewport holding all the points, if there are any.
+    fn around<'a, I>(points: I) -> Option<Viewport>
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        let mut points = points.into_iter();
+        let first = *points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), p| {
+            (
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        });
+
+        let size = max - min;
+        Some(Viewport::new(min, size.x as usize + 1, size.y as usize + 1))
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        let d = p - self.origin;
+        d.x >= 0 && d.y >= 0 && (d.x as usize) < self.width && (d.y as usize) < self.height
+    }
+}
+
+/// Parses `x,y,width,height`.
+impl FromStr for Viewport {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Viewport> {
+        let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+        ensure!(
+            parts.len() == 4,
+            "expected a viewport as x,y,width,height, got {}",
+            s
+        );
+
+        let origin = Point::new(parts[0].parse()?, parts[1].parse()?);
+        let (width, height) = (parts[2].parse()?, parts[3].parse()?);
+        ensure!(width > 0 && height > 0, "the viewport {} is empty", s);
+
+        Ok(Viewport::new(origin, width, height))
+    }
+}
+
+pub struct Recorder {
+    every: usize,
+    scale: usize,
+    delay: u16,
+    background: Rgb,
+    viewport: Option<Viewport>,
+    steps: usize,
+    frames: Vec<Vec<(Point, Rgb)>>,
+}
+
+impl Recorder {
+    /// Keeps the first step and every `every`-th one after it, drawn one
+    /// pixel per point on black until told otherwise.
+    pub fn new(every: usize) -> Recorder {
+        Recorder {
+            every: every.max(1),
+            scale: 1,
+            delay: 10,
+            background: Rgb::BLACK,
+            viewport: None,
+            steps: 0,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Blows every point up to a `factor` by `factor` square.
+    pub fn scale(mut self, factor: usize) -> Recorder {
+        self.scale = factor.max(1);
+        self
+    }
+
+    /// Hundredths of a second between frames.
+    pub fn delay(mut self, delay: u16) -> Recorder {
+        self.delay = delay;
+        self
+    }
+
+    pub fn background(mut self, colour: Rgb) -> Recorder {
+        self.background = colour;
+        self
+    }
+
+    /// Only shows what is inside `viewport`, instead of everything ever drawn.
+    pub fn viewport(mut self, viewport: Viewport) -> Recorder {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    /// Called before every step of the simulation, and only draws the state
+    /// if it is to become a frame.
+    pub fn step<F, I>(&mut self, draw: F)
+    where
+        F: FnOnce() -> I,
+        I: IntoIterator<Item = (Point, Rgb)>,
+    {
+        if self.steps % self.every == 0 {
+            self.record(draw());
+        }
+
+        self.steps += 1;
+    }
+
+    /// Called once the simulation is done, so the final state is always shown.
+    pub fn finish<F, I>(&mut self, draw: F)
+    where
+        F: FnOnce() -> I,
+        I: IntoIterator<Item = (Point, Rgb)>,
+    {
+        self.record(draw());
+    }
+
+    fn record<I: IntoIterator<Item = (Point, Rgb)>>(&mut self, points: I) {
+        let frame = match self.viewport {
+            Some(viewport) => points
+                .into_iter()
+                .filter(|&(p, _)| viewport.contains(p))
+                .collect(),
+            None => points.into_iter().collect(),
+        };
+
+        self.frames.push(frame);
+    }
+
+    /// Number of frames recorded so far.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The viewport, or else just enough to show every frame in full.
+    fn bounds(&self) -> Result<Viewport> {
+        let all = self.frames.iter().flat_map(|f| f.iter().map(|(p, _)| p));
+        let bounds = self
+            .viewport
+            .or_else(|| Viewport::around(all))
+            .unwrap_or_else(|| Viewport::new(Point::origin(), 1, 1));
+
+        let (width, height) = (bounds.width * self.scale, bounds.height * self.scale);
+        ensure!(
+            width <= u16::max_value() as usize && height <= u16::max_value() as usize,
+            "a {}x{} animation is too large for a GIF, try a smaller viewport",
+            width,
+            height
+        );
+
+        Ok(bounds)
+    }
+
+    fn draw(&self, frame: &[(Point, Rgb)], bounds: Viewport) -> Image {
+        let mut image = Image::new(bounds.width, bounds.height, self.background);
+        for &(p, colour) in frame {
+            image.set(p - bounds.origin, colour);
+        }
+
+        image.scale(self.scale)
+    }
+
+    pub fn to_gif(&self) -> Result<Vec<u8>> {
+        ensure!(!self.is_empty(), "there are no frames to animate");
+        let bounds = self.bounds()?;
+        let (width, height) = (bounds.width * self.scale, bounds.height * self.scale);
+
+        let mut palette = vec![self.background];
+        let mut index = FnvHashMap::default();
+        index.insert(self.background, 0);
+        for frame in &self.frames {
+            for &(_, colour) in frame {
+                if let Entry::Vacant(entry) = index.entry(colour) {
+                    entry.insert(palette.len());
+                    palette.push(colour);
+                }
+            }
+        }
+        ensure!(
+            palette.len() <= 256,
+            "a GIF has room for 256 colours, not {}",
+            palette.len()
+        );
+
+        // The palette holds a power of two colours, at least two of them
+        let bits = (1..8).find(|&b| palette.len() <= 1 << b).unwrap_or(8);
+        palette.resize(1 << bits, Rgb::BLACK);
+
+        let mut out = b"GIF89a".to_vec();
+        out.extend(&le16(width as u16));
+        out.extend(&le16(height as u16));
+        // A global palette of 8 bits per channel, not sorted
+        out.extend(&[0xf0 | (bits - 1), 0, 0]);
+        for &Rgb(r, g, b) in &palette {
+            out.extend(&[r, g, b]);
+        }
+
+        // Loops forever
+        out.extend(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+        let min_code_bits = bits.max(2);
+        for frame in &self.frames {
+            // How long to show the frame, with no transparent colour
+            out.extend(&[0x21, 0xf9, 4, 0]);
+            out.extend(&le16(self.delay));
+            out.extend(&[0, 0]);
+
+            // Covers the whole screen, with no palette of its own
+            out.push(0x2c);
+            out.extend(&[0, 0, 0, 0]);
+            out.extend(&le16(width as u16));
+            out.extend(&le16(height as u16));
+            out.push(0);
+
+            let image = self.draw(frame, bounds);
+            let indices: Vec<u8> = image.pixels().iter().map(|(_, c)| index[c] as u8).collect();
+            out.push(min_code_bits);
+            for block in lzw(&indices, min_code_bits).chunks(255) {
+                out.push(block.len() as u8);
+                out.extend(block);
+            }
+            out.push(0);
+        }
+
+        out.push(0x3b);
+        Ok(out)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        ensure!(
+            path.extension().and_then(|e| e.to_str()) == Some("gif"),
+            "{} should end in .gif",
+            path.display()
+        );
+
+        let bytes = self.to_gif()?;
+        fs::write(path, bytes).map_err(|e| format_err!("failed to write {}: {}", path.display(), e))
+    }
+}
+
+fn le16(n: u16) -> [u8; 2] {
+    [n as u8, (n >> 8) as u8]
+}
+
+/// Packs codes of varying width, least significant bit first.
+struct Bits {
+    out: Vec<u8>,
+    buffer: u32,
+    len: u8,
+}
+
+impl Bits {
+    fn new() -> Bits {
+        Bits {
+            out: Vec::new(),
+            buffer: 0,
+            len: 0,
+        }
+    }
+
+    fn write(&mut self, code: u16, bits: u8) {
+        self.buffer |= u32::from(code) << self.len;
+        self.len += bits;
+        while self.len >= 8 {
+            self.out.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.out.push(self.buffer as u8);
+        }
+
+        self.out
+    }
+}
+
+/// The variable-length LZW compression GIF uses, starting over with a clear
+/// code whenever the table of codes is full.
+fn lzw(indices: &[u8], min_code_bits: u8) -> Vec<u8> {
+    let clear = 1u16 << min_code_bits;
+    let end = clear + 1;
+
+    let mut table: FnvHashMap<(u16, u8), u16> = FnvHashMap::default();
+    let mut next = end + 1;
+    let mut bits = min_code_bits + 1;
+    let mut out = Bits::new();
+    out.write(clear, bits);
+
+    let mut prefix: Option<u16> = None;
+    for &k in indices {
+        let p = match prefix {
+            Some(p) => p,
+            None => {
+                prefix = Some(u16::from(k));
+                continue;
+            }
+        };
+
+        if let Some(&code) = table.get(&(p, k)) {
+            prefix = Some(code);
+            continue;
+        }
+
+        out.write(p, bits);
+        if next < 1 << MAX_CODE_BITS {
+            table.insert((p, k), next);
+            next += 1;
+            // The decoder adds its codes one step later, so only needs the
+            // extra bit once the code after this one is taken
+            if next > 1 << bits && bits < MAX_CODE_BITS {
+                bits += 1;
+            }
+        } else {
+            out.write(clear, bits);
+            table.clear();
+            next = end + 1;
+            bits = min_code_bits + 1;
+        }
+
+        prefix = Some(u16::from(k));
+    }
+
+    if let Some(p) = prefix {
+        out.write(p, bits);
+        // Reading that code fills in one more entry on the decoder's side
+        if next + 1 > 1 << bits && bits < MAX_CODE_BITS {
+            bits += 1;
+        }
+    }
+
+    out.write(end, bits);
+    out.finish()
+}
+
+/// Records the frames of a day's simulation, with the same parameters as its
+/// solver.
+pub type Animator = fn(&str, &Overrides, &mut Recorder) -> Result<()>;
+
+pub fn animator(edition: u32, day: u32) -> Option<Animator> {
+    use seventeen;
+
+    let animator: Animator = match (edition, day) {
+        (2017, 20) => plain!(seventeen::day20::animate, recorder),
+        (2017, 21) => tuned!(seventeen::day21::animate, recorder),
+        (2017, 22) => tuned!(seventeen::day22::animate, recorder),
+        (2017, 25) => plain!(seventeen::day25::animate, recorder),
+        _ => return None,
+    };
+
+    Some(animator)
+}
+
+pub fn animate(
+    edition: u32,
+    day: u32,
+    input: &str,
+    overrides: &Overrides,
+    recorder: &mut Recorder,
+) -> Result<()> {
+    match animator(edition, day) {
+        Some(animate) => animate(input, overrides, recorder),
+        None => Err(missing("animation", edition, day)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Undoes `lzw`, the way a GIF decoder would.
+    fn unlzw(data: &[u8], min_code_bits: u8) -> Vec<u8> {
+        let clear = 1usize << min_code_bits;
+        let end = clear + 1;
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut bits = min_code_bits + 1;
+        let mut previous: Option<usize> = None;
+        let mut out = Vec::new();
+        let mut pos = 0;
+
+        loop {
+            let mut code = 0;
+            for i in 0..bits as usize {
+                let bit = (data[(pos + i) / 8] >> ((pos + i) % 8)) & 1;
+                code |= (bit as usize) << i;
+            }
+            pos += bits as usize;
+
+            if code == clear {
+                table = (0..clear).map(|i| vec![i as u8]).collect();
+                table.push(Vec::new());
+                table.push(Vec::new());
+                bits = min_code_bits + 1;
+                previous = None;
+                continue;
+            } else if code == end {
+                return out;
+            }
+
+            let entry = match previous {
+                Some(prev) => {
+                    let mut entry = table[prev].clone();
+                    let first = if code < table.len() {
+                        table[code][0]
+                    } else {
+                        entry[0]
+                    };
+                    entry.push(first);
+                    if table.len() < 1 << MAX_CODE_BITS {
+                        table.push(entry);
+                    }
+                    table[code].clone()
+                }
+                None => table[code].clone(),
+            };
+
+            if table.len() == 1 << bits && bits < MAX_CODE_BITS {
+                bits += 1;
+            }
+
+            out.extend(&entry);
+            previous = Some(code);
+        }
+    }
+
+    #[test]
+    fn compression() {
+        let runs: Vec<u8> = (0..5000).map(|i| (i / 7 % 3) as u8).collect();
+        let noise: Vec<u8> = (0..20_000u32).map(|i| (i * 7919 % 251) as u8).collect();
+        for &(ref indices, bits) in &[(runs, 2), (noise, 8), (vec![], 2), (vec![1], 2)] {
+            assert_eq!(&unlzw(&lzw(indices, bits), bits), indices);
+        }
+    }
+
+    #[test]
+    fn sampling() {
+        let mut recorder = Recorder::new(3).viewport(Viewport::new(Point::origin(), 4, 1));
+        for i in 0..7 {
+            recorder.step(|| vec![(Point::new(i, 0), Rgb::WHITE)]);
+        }
+        recorder.finish(|| vec![(Point::new(7, 0), Rgb::WHITE)]);
+
+        assert_eq!(recorder.len(), 4);
+        assert_eq!(recorder.frames[1], vec![(Point::new(3, 0), Rgb::WHITE)]);
+        assert!(recorder.frames[3].is_empty());
+    }
+
+    #[test]
+    fn gif() {
+        let mut recorder = Recorder::new(1).scale(2);
+        recorder.step(|| vec![(Point::new(-1, 0), Rgb::RED)]);
+        recorder.step(|| vec![(Point::new(1, 2), Rgb::BLUE)]);
+
+        let gif = recorder.to_gif().unwrap();
+        assert_eq!(&gif[..6], b"GIF89a");
+        // 6x6, with black, red and blue padded to four colours
+        assert_eq!(&gif[6..11], &[6, 0, 6, 0, 0xf1]);
+        assert_eq!(&gif[13..22], &[0, 0, 0, 220, 40, 40, 60, 90, 230]);
+        assert_eq!(gif[gif.len() - 1], 0x3b);
+
+        assert!("1,2,3".parse::<Viewport>().is_err());
+        assert_eq!(
+            "-5,2,30,20".parse::<Viewport>().unwrap(),
+            Viewport::new(Point::new(-5, 2), 30, 20)
+        );
+    }
+}
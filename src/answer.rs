@@ -0,0 +1,138 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde::{Serialize, Serializer};
+
+/// The answer to one part of a puzzle, keeping the type the solver produced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    /// Raw bytes, shown as lowercase hex
+    Bytes(Vec<u8>),
+    /// Text spanning several lines, e.g. letters drawn on a screen
+    Lines(Vec<String>),
+}
+
+impl Answer {
+    pub fn is_multiline(&self) -> bool {
+        match self {
+            Answer::Lines(lines) => lines.len() > 1,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(v) => write!(f, "{}", v),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Bytes(bytes) => {
+                for byte in bytes {
+                    write!(f, "{:02x}", byte)?;
+                }
+
+                Ok(())
+            }
+            Answer::Lines(lines) => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+
+/// Integers stay numbers and lines become an array; everything else is a string.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Integer(v) => serializer.serialize_i64(*v),
+            Answer::Text(s) => serializer.serialize_str(s),
+            Answer::Bytes(_) => serializer.serialize_str(&self.to_string()),
+            Answer::Lines(lines) => lines.serialize(serializer),
+        }
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(v: $t) -> Answer {
+                    Answer::Integer(i64::from(v))
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i8, i16, i32, i64, u8, u16, u32);
+
+/// Values beyond the range of an `i64` are kept exactly, as text.
+macro_rules! from_wide_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(v: $t) -> Answer {
+                    i64::try_from(v)
+                        .map(Answer::Integer)
+                        .unwrap_or_else(|_| Answer::Text(v.to_string()))
+                }
+            }
+        )*
+    };
+}
+
+from_wide_integer!(u64, usize, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+impl<'a> From<&'a str> for Answer {
+    fn from(s: &'a str) -> Answer {
+        Answer::Text(s.to_owned())
+    }
+}
+
+impl From<char> for Answer {
+    fn from(c: char) -> Answer {
+        Answer::Text(c.to_string())
+    }
+}
+
+impl From<Vec<u8>> for Answer {
+    fn from(bytes: Vec<u8>) -> Answer {
+        Answer::Bytes(bytes)
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(lines: Vec<String>) -> Answer {
+        Answer::Lines(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(Answer::from(-12).to_string(), "-12");
+        assert_eq!(Answer::from("abc").to_string(), "abc");
+        assert_eq!(Answer::from(vec![0x0fu8, 0xa2]).to_string(), "0fa2");
+        let lines = vec!["#..".to_owned(), ".#.".to_owned()];
+        assert_eq!(Answer::from(lines).to_string(), "#..\n.#.");
+    }
+
+    #[test]
+    fn wide_integers() {
+        assert_eq!(Answer::from(7u64), Answer::Integer(7));
+        assert_eq!(Answer::from(-7isize), Answer::Integer(-7));
+        assert_eq!(
+            Answer::from(u64::max_value()),
+            Answer::Text("18446744073709551615".to_owned())
+        );
+    }
+}
@@ -0,0 +1,605 @@
+//! Cellular automata and ants walking over a grid of states, with their rules
+//! written in a small text format:
+//!
+//! ```text
+//! // Conway's Life
+//! states: . #
+//! neighbourhood: moore
+//! edges: bounded
+//! . -> # if 3 #
+//! # -> . unless 2,3 #
+//! ```
+//!
+//! The first state is the background, which fills the world outside the
+//! grid. A rule `a -> b if 2,3 c` changes a cell from `a` to `b` when as many
+//! of its neighbours are `c`, and `unless` when they are not; the first rule
+//! that applies wins, and cells without one stay as they are. A rule
+//! `a -> b turn left` is for the agents instead: an agent on an `a` cell
+//! turns, leaves a `b` behind and moves forward. Agents can turn `left`,
+//! `right`, `straight` or `reverse`.
+//!
+//! Worlds are infinite unless `edges: bounded` says otherwise, in which case
+//! agents walking off the edge are gone. Infinite worlds can't have a rule
+//! that changes the background cells far away from everything else. `count:
+//! c` counts the cells changed into `c`. Lines starting with `//` are
+//! comments.
+
+use std::fmt;
+use std::str::FromStr;
+
+use nom::{anychar, space};
+
+use geom::{Direction, Point};
+use grid::Grid;
+use parsers::{self, integer, Input};
+use Result;
+
+/// The cells that count as neighbours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// The eight cells sharing an edge or a corner
+    Moore,
+    /// The four cells sharing an edge
+    VonNeumann,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Turn {
+    Left,
+    Right,
+    Straight,
+    Reverse,
+}
+
+impl Turn {
+    fn apply(self, d: Direction) -> Direction {
+        match self {
+            Turn::Left => d.left(),
+            Turn::Right => d.right(),
+            Turn::Straight => d,
+            Turn::Reverse => d.reverse(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edges {
+    Infinite,
+    Bounded,
+}
+
+/// States are numbered in the order they are listed.
+type State = u8;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Action {
+    Turn(Turn),
+    /// Whether the count of neighbours in a state is one of these
+    Neighbours {
+        unless: bool,
+        counts: Vec<usize>,
+        of: char,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CellRule {
+    from: State,
+    to: State,
+    unless: bool,
+    /// Bit `n` is set if `n` neighbours match
+    counts: u32,
+    of: State,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    states: Vec<char>,
+    neighbourhood: Neighbourhood,
+    edges: Edges,
+    counted: Option<State>,
+    cells: Vec<CellRule>,
+    /// What agents do on each state
+    turns: Vec<Option<(State, Turn)>>,
+}
+
+named!(
+    turn(Input) -> Turn,
+    alt!(
+        value!(Turn::Left, tag!("left")) |
+        value!(Turn::Right, tag!("right")) |
+        value!(Turn::Straight, tag!("straight")) |
+        value!(Turn::Reverse, tag!("reverse"))
+    )
+);
+
+named!(
+    action(Input) -> Action,
+    alt!(
+        map!(preceded!(pair!(tag!("turn"), space), turn), Action::Turn) |
+        do_parse!(
+            unless: alt!(value!(false, tag!("if")) | value!(true, tag!("unless"))) >>
+            space >>
+            counts: separated_nonempty_list!(char!(','), integer) >>
+            space >>
+            of: anychar >>
+            (Action::Neighbours { unless, counts, of })
+        )
+    )
+);
+
+named!(
+    rule(Input) -> (char, char, Action),
+    do_parse!(
+        from: anychar >>
+        space >>
+        tag!("->") >>
+        space >>
+        to: anychar >>
+        space >>
+        action: action >>
+        (from, to, action)
+    )
+);
+
+impl Rules {
+    fn state(&self, c: char) -> Result<State> {
+        match self.states.iter().position(|&s| s == c) {
+            Some(i) => Ok(i as State),
+            None => bail!("unknown state: {}", c),
+        }
+    }
+
+    fn add_rule(&mut self, (from, to, action): (char, char, Action)) -> Result<()> {
+        let (from, to) = (self.state(from)?, self.state(to)?);
+
+        match action {
+            Action::Turn(turn) => {
+                let previous = &mut self.turns[from as usize];
+                ensure!(
+                    previous.is_none(),
+                    "agents already have a rule for {}",
+                    self.states[from as usize]
+                );
+                *previous = Some((to, turn));
+            }
+            Action::Neighbours { unless, counts, of } => {
+                let of = self.state(of)?;
+                let mut mask = 0;
+                for n in counts {
+                    ensure!(n <= 8, "cells have at most 8 neighbours, not {}", n);
+                    mask |= 1 << n;
+                }
+
+                self.cells.push(CellRule {
+                    from,
+                    to,
+                    unless,
+                    counts: mask,
+                    of,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "states" => bail!("the states must come first"),
+            "neighbourhood" => {
+                self.neighbourhood = match value {
+                    "moore" => Neighbourhood::Moore,
+                    "von-neumann" => Neighbourhood::VonNeumann,
+                    other => bail!("unknown neighbourhood: {}", other),
+                }
+            }
+            "edges" => {
+                self.edges = match value {
+                    "infinite" => Edges::Infinite,
+                    "bounded" => Edges::Bounded,
+                    other => bail!("unknown edges: {}", other),
+                }
+            }
+            "count" => {
+                let mut chars = value.chars();
+                let c = chars.next().unwrap_or(' ');
+                ensure!(chars.next().is_none(), "states are single characters");
+                self.counted = Some(self.state(c)?);
+            }
+            other => bail!("unknown setting: {}", other),
+        }
+
+        Ok(())
+    }
+
+    /// Whether `rule` applies to a background cell with only background
+    /// around it.
+    fn fires_on_background(&self, rule: &CellRule) -> bool {
+        let all = match self.neighbourhood {
+            Neighbourhood::Moore => 8,
+            Neighbourhood::VonNeumann => 4,
+        };
+        let n = if rule.of == 0 { all } else { 0 };
+        rule.from == 0 && (rule.counts & (1 << n) != 0) != rule.unless
+    }
+}
+
+impl FromStr for Rules {
+    type Err = ::failure::Error;
+
+    fn from_str(s: &str) -> Result<Rules> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|&(_, line)| !line.is_empty() && !line.starts_with("//"));
+
+        let states = match lines.next() {
+            Some((_, line)) => match parsers::parse(line, parsers::key_value)? {
+                ("states", states) => states
+                    .split_whitespace()
+                    .map(|s| match s.chars().count() {
+                        1 => Ok(s.chars().next().unwrap()),
+                        _ => Err(format_err!("states are single characters, not {}", s)),
+                    })
+                    .collect::<Result<Vec<char>>>()?,
+                _ => bail!("the rules should start with the states"),
+            },
+            None => bail!("there are no rules"),
+        };
+        ensure!(
+            !states.is_empty() && states.len() <= 256,
+            "there should be between 1 and 256 states, not {}",
+            states.len()
+        );
+
+        let mut rules = Rules {
+            neighbourhood: Neighbourhood::Moore,
+            edges: Edges::Infinite,
+            counted: None,
+            cells: Vec::new(),
+            turns: vec![None; states.len()],
+            states,
+        };
+
+        for (n, line) in lines {
+            let added = if line.contains("->") {
+                parsers::parse(line, rule).and_then(|r| rules.add_rule(r))
+            } else {
+                parsers::parse(line, parsers::key_value).and_then(|(k, v)| rules.set(k, v))
+            };
+            added.map_err(|e| format_err!("line {}: {}", n, e))?;
+        }
+
+        if rules.edges == Edges::Infinite {
+            let background = rules.cells.iter().find(|r| rules.fires_on_background(r));
+            if let Some(rule) = background {
+                ensure!(
+                    rule.to == 0,
+                    "{} -> {} would change the endless background, which needs edges: bounded",
+                    rules.states[0],
+                    rules.states[rule.to as usize]
+                );
+            }
+        }
+
+        Ok(rules)
+    }
+}
+
+/// Something walking over the world.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Agent {
+    pub position: Point,
+    pub direction: Direction,
+}
+
+#[derive(Clone, Debug)]
+pub struct Automaton {
+    rules: Rules,
+    cells: Grid<State>,
+    /// Where the origin of the world is in `cells`
+    offset: Point,
+    agents: Vec<Agent>,
+    count: usize,
+    steps: usize,
+}
+
+impl Automaton {
+    /// A world starting as `grid`, with its top left corner at the origin.
+    pub fn new(rules: Rules, grid: &Grid<char>) -> Result<Automaton> {
+        let cells = Grid::from_rows(
+            grid.rows()
+                .map(|row| row.iter().map(|&c| rules.state(c)).collect())
+                .collect::<Result<_>>()?,
+        )?;
+
+        Ok(Automaton {
+            rules,
+            cells,
+            offset: Point::new(0, 0),
+            agents: Vec::new(),
+            count: 0,
+            steps: 0,
+        })
+    }
+
+    pub fn add_agent(&mut self, position: Point, direction: Direction) {
+        self.agents.push(Agent {
+            position,
+            direction,
+        });
+    }
+
+    pub fn agents(&self) -> &[Agent] {
+        &self.agents
+    }
+
+    /// The number of cells changed into the counted state so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// The state at `p`, which is the background outside the grid.
+    pub fn get(&self, p: Point) -> char {
+        self.rules.states[self.state(p) as usize]
+    }
+
+    fn state(&self, p: Point) -> State {
+        self.cells.get(p + self.offset).cloned().unwrap_or(0)
+    }
+
+    /// Changes the state at `p`, growing an infinite world to fit it.
+    fn set(&mut self, p: Point, state: State) {
+        while !self.cells.contains(p + self.offset) {
+            let n = self.cells.width().max(self.cells.height()).max(1);
+            self.cells.grow(n, 0);
+            self.offset += Point::new(n as i32, n as i32);
+        }
+
+        let cell = &mut self.cells[p + self.offset];
+        if *cell != state && Some(state) == self.rules.counted {
+            self.count += 1;
+        }
+        *cell = state;
+    }
+
+    /// The cells that aren't the background.
+    pub fn cells<'a>(&'a self) -> impl Iterator<Item = (Point, char)> + 'a {
+        self.cells
+            .iter()
+            .filter(|&(_, &state)| state != 0)
+            .map(move |(p, &state)| (p - self.offset, self.rules.states[state as usize]))
+    }
+
+    /// The number of cells in state `c`, within the grid.
+    pub fn population(&self, c: char) -> usize {
+        self.cells
+            .iter()
+            .filter(|&(_, &state)| self.rules.states[state as usize] == c)
+            .count()
+    }
+
+    /// Applies the rules of the cells to all of them at once, then moves
+    /// every agent. Returns false once nothing is left to change anything.
+    pub fn step(&mut self) -> bool {
+        if !self.rules.cells.is_empty() {
+            self.generation();
+        }
+
+        for i in 0..self.agents.len() {
+            self.walk(i);
+        }
+
+        if self.rules.edges == Edges::Bounded {
+            let cells = &self.cells;
+            let offset = self.offset;
+            self.agents.retain(|a| cells.contains(a.position + offset));
+        }
+
+        self.steps += 1;
+        !self.rules.cells.is_empty() || !self.agents.is_empty()
+    }
+
+    pub fn run(&mut self, n: usize) {
+        for _ in 0..n {
+            if !self.step() {
+                return;
+            }
+        }
+    }
+
+    fn walk(&mut self, i: usize) {
+        let Agent {
+            position,
+            direction,
+        } = self.agents[i];
+
+        let direction = match self.rules.turns[self.state(position) as usize] {
+            Some((next, turn)) => {
+                self.set(position, next);
+                turn.apply(direction)
+            }
+            None => direction,
+        };
+
+        self.agents[i] = Agent {
+            position: position.step(direction),
+            direction,
+        };
+    }
+
+    fn neighbours(&self, p: Point, of: State) -> usize {
+        let count = |q: Point| (self.cells.get(q).cloned().unwrap_or(0) == of) as usize;
+        match self.rules.neighbourhood {
+            Neighbourhood::Moore => p.neighbours8().map(count).sum(),
+            Neighbourhood::VonNeumann => p.neighbours4().map(count).sum(),
+        }
+    }
+
+    fn generation(&mut self) {
+        // Anything new in an infinite world can only appear next to the
+        // cells that are already there
+        if self.rules.edges == Edges::Infinite {
+            let (w, h) = (self.cells.width() as i32, self.cells.height() as i32);
+            let crowded = self
+                .cells
+                .iter()
+                .any(|(p, &s)| s != 0 && (p.x == 0 || p.y == 0 || p.x == w - 1 || p.y == h - 1));
+            if crowded {
+                self.cells.grow(1, 0);
+                self.offset += Point::new(1, 1);
+            }
+        }
+
+        let mut counted = 0;
+        let next = Grid::from_fn(self.cells.width(), self.cells.height(), |p| {
+            let state = self.cells[p];
+            let changed = self.rules.cells.iter().find(|rule| {
+                rule.from == state
+                    && (rule.counts & (1 << self.neighbours(p, rule.of)) != 0) != rule.unless
+            });
+
+            match changed {
+                Some(rule) => {
+                    if Some(rule.to) == self.rules.counted && rule.to != state {
+                        counted += 1;
+                    }
+                    rule.to
+                }
+                None => state,
+            }
+        });
+
+        self.cells = next;
+        self.count += counted;
+    }
+}
+
+/// The grid as far as it has grown, one character per cell.
+impl fmt::Display for Automaton {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let states = &self.rules.states;
+        write!(f, "{}", self.cells.render(|&s| states[s as usize]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geom::Direction::North;
+
+    const LIFE: &str = "
+// Conway's Life
+states: . #
+edges: bounded
+. -> # if 3 #
+# -> . unless 2,3 #
+";
+
+    const LANGTON: &str = "
+states: . #
+count: #
+. -> # turn right
+# -> . turn left
+";
+
+    fn automaton(rules: &str, grid: &str) -> Automaton {
+        let grid = Grid::from_chars(grid, Ok).unwrap();
+        Automaton::new(rules.parse().unwrap(), &grid).unwrap()
+    }
+
+    #[test]
+    fn life() {
+        let mut blinker = automaton(LIFE, ".....\n..#..\n..#..\n..#..\n.....");
+        blinker.step();
+        assert_eq!(blinker.to_string(), ".....\n.....\n.###.\n.....\n.....");
+        blinker.step();
+        assert_eq!(blinker.to_string(), ".....\n..#..\n..#..\n..#..\n.....");
+
+        // A glider in an infinite world comes back the same, one cell further
+        let infinite = LIFE.replace("bounded", "infinite");
+        let mut glider = automaton(&infinite, ".#.\n..#\n###");
+        let before: Vec<(Point, char)> = glider.cells().collect();
+        glider.run(4);
+        let after: Vec<(Point, char)> = glider
+            .cells()
+            .map(|(p, c)| (p - Point::new(1, 1), c))
+            .collect();
+        assert_eq!(before, after);
+        assert_eq!(glider.population('#'), 5);
+    }
+
+    #[test]
+    fn ants() {
+        let mut ant = automaton(LANGTON, ".");
+        ant.add_agent(Point::new(0, 0), North);
+        ant.run(11_000);
+        assert_eq!(ant.steps(), 11_000);
+        assert_eq!(ant.cells().count(), ant.population('#'));
+
+        // After about 10,000 steps the ant builds a highway, which takes 104
+        // steps to go 2 cells further diagonally, with 12 more black cells
+        let (before, black) = (ant.agents()[0].position, ant.population('#'));
+        ant.run(104);
+        let moved = ant.agents()[0].position - before;
+        assert_eq!((moved.x.abs(), moved.y.abs()), (2, 2));
+        assert_eq!(ant.population('#'), black + 12);
+
+        let bounded = LANGTON.replace("count", "edges: bounded\ncount");
+        let mut ant = automaton(&bounded, "...\n...\n...");
+        ant.add_agent(Point::new(1, 1), North);
+        ant.run(100);
+        assert!(ant.agents().is_empty());
+        assert!(ant.steps() < 100);
+
+        // Leaving a cell as it was doesn't count as changing it
+        let rules = "states: . #\ncount: #\n# -> # turn left\n. -> # turn right";
+        let mut ant = automaton(rules, "#.");
+        ant.add_agent(Point::new(0, 0), North);
+        ant.run(5);
+        assert_eq!((ant.population('#'), ant.count()), (4, 3));
+    }
+
+    #[test]
+    fn rules() {
+        let rules: Rules = LANGTON.parse().unwrap();
+        assert_eq!(
+            rules.turns,
+            vec![Some((1, Turn::Right)), Some((0, Turn::Left))]
+        );
+
+        let err = |s: &str| s.parse::<Rules>().unwrap_err().to_string();
+        assert_eq!(err(""), "there are no rules");
+        assert_eq!(err("count: #"), "the rules should start with the states");
+        assert_eq!(
+            err("states: . #\n. -> x turn left"),
+            "line 2: unknown state: x"
+        );
+        assert_eq!(
+            err("states: . #\n\n. -> # if 9 #"),
+            "line 3: cells have at most 8 neighbours, not 9"
+        );
+        assert_eq!(
+            err("states: . #\n. -> # turn up"),
+            "line 2: expected Alternative at \"turn up\""
+        );
+        assert_eq!(
+            err("states: . #\n. -> # unless 3 #"),
+            ". -> # would change the endless background, which needs edges: bounded"
+        );
+        assert!("states: . #\nedges: bounded\n. -> # unless 3 #"
+            .parse::<Rules>()
+            .is_ok());
+        assert!("states: . #\n. -> . if 0 #\n. -> # if 0 #"
+            .parse::<Rules>()
+            .is_ok());
+        let grid = Grid::from_chars(".x", Ok).unwrap();
+        assert!(Automaton::new(LIFE.parse().unwrap(), &grid).is_err());
+    }
+}
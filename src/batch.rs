@@ -0,0 +1,308 @@
+use std::any::Any;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use failure::err_msg;
+
+use params::Overrides;
+use {Answer, Answers, Result, Solver};
+
+/// What happened when a solver was run on a single input.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Solved(Answers),
+    Failed(String),
+    Panicked(String),
+    /// The solver was still running after this many seconds
+    TimedOut(u64),
+}
+
+impl Outcome {
+    pub fn is_solved(&self) -> bool {
+        match self {
+            Outcome::Solved(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn into_result(self) -> Result<Answers> {
+        match self {
+            Outcome::Solved(answers) => Ok(answers),
+            Outcome::Failed(e) => Err(err_msg(e)),
+            Outcome::Panicked(msg) => bail!("solver panicked: {}", msg),
+            Outcome::TimedOut(secs) => bail!("solver timed out after {}s", secs),
+        }
+    }
+}
+
+pub(crate) fn panic_message(payload: &(Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "<unknown panic payload>".to_owned()
+    }
+}
+
+/// Runs `f` with the panic hook silenced, so panics that are caught and
+/// reported anyway don't clutter the output.
+pub(crate) fn quietly<T, F: FnOnce() -> T>(f: F) -> T {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| ()));
+    let result = f();
+    panic::set_hook(hook);
+    result
+}
+
+/// Runs `solver` on `input`, turning both errors and panics into an `Outcome`.
+pub fn solve_guarded(solver: Solver, input: &str, overrides: &Overrides) -> Outcome {
+    match panic::catch_unwind(|| solver(input, overrides)) {
+        Ok(Ok(answers)) => Outcome::Solved(answers),
+        Ok(Err(e)) => Outcome::Failed(e.to_string()),
+        Err(payload) => Outcome::Panicked(panic_message(&*payload)),
+    }
+}
+
+/// Like `solve_guarded`, but gives up once `timeout` has passed. The solver
+/// runs on its own thread, which is left behind if it does not finish in time.
+pub fn solve_timed(
+    solver: Solver,
+    input: &str,
+    overrides: &Overrides,
+    timeout: Option<Duration>,
+) -> Outcome {
+    let input = input.to_owned();
+    let overrides = overrides.clone();
+    with_timeout(timeout, move || solve_guarded(solver, &input, &overrides))
+}
+
+/// Runs `f` on its own thread if there is a `timeout`, giving up on it once
+/// the timeout has passed.
+pub(crate) fn with_timeout<F>(timeout: Option<Duration>, f: F) -> Outcome
+where
+    F: FnOnce() -> Outcome + Send + 'static,
+{
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return f(),
+    };
+
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(f());
+    });
+
+    match rx.recv_timeout(timeout) {
+        Ok(outcome) => outcome,
+        Err(RecvTimeoutError::Timeout) => Outcome::TimedOut(timeout.as_secs()),
+        Err(RecvTimeoutError::Disconnected) => {
+            Outcome::Panicked("solver thread exited without an answer".to_owned())
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Entry {
+    pub input: String,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    entries: Vec<Entry>,
+}
+
+impl Report {
+    pub fn failures(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| !e.outcome.is_solved())
+            .count()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cells: Vec<(&str, String, String)> = self
+            .entries
+            .iter()
+            .map(|Entry { input, outcome }| {
+                let (part1, part2) = match outcome {
+                    Outcome::Solved(Answers { part1, part2 }) => (
+                        part1.as_ref().map_or("-".to_owned(), Answer::to_string),
+                        part2.as_ref().map_or("-".to_owned(), Answer::to_string),
+                    ),
+                    Outcome::Failed(e) => (format!("ERROR: {}", e), String::new()),
+                    Outcome::Panicked(msg) => (format!("PANIC: {}", msg), String::new()),
+                    Outcome::TimedOut(secs) => {
+                        (format!("TIMEOUT: after {}s", secs), String::new())
+                    }
+                };
+
+                (input.as_str(), part1, part2)
+            })
+            .collect();
+
+        let name_width = cells.iter().map(|c| c.0.len()).max().unwrap_or(0).max(5);
+        let part1_width = cells
+            .iter()
+            .filter(|c| !c.2.is_empty())
+            .map(|c| c.1.len())
+            .max()
+            .unwrap_or(0)
+            .max(6);
+
+        writeln!(
+            f,
+            "{:<nw$}  {:<pw$}  {}",
+            "input",
+            "part 1",
+            "part 2",
+            nw = name_width,
+            pw = part1_width
+        )?;
+
+        for (name, part1, part2) in cells {
+            let line = format!(
+                "{:<nw$}  {:<pw$}  {}",
+                name,
+                part1,
+                part2,
+                nw = name_width,
+                pw = part1_width
+            );
+            writeln!(f, "{}", line.trim_right())?;
+        }
+
+        Ok(())
+    }
+}
+
+fn input_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+
+    paths.retain(|p| p.is_file());
+    paths.sort();
+    ensure!(!paths.is_empty(), "no inputs found in {}", dir.display());
+
+    Ok(paths)
+}
+
+/// Runs `solver` over every file in `dir`, in order of their file names.
+pub fn run(
+    solver: Solver,
+    dir: &Path,
+    overrides: &Overrides,
+    timeout: Option<Duration>,
+) -> Result<Report> {
+    let paths = input_files(dir)?;
+
+    let entries = quietly(|| {
+        paths
+            .into_iter()
+            .map(|path| {
+                let input = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.display().to_string());
+
+                let outcome = match fs::read_to_string(&path) {
+                    Ok(contents) => solve_timed(solver, &contents, overrides, timeout),
+                    Err(e) => Outcome::Failed(e.to_string()),
+                };
+
+                info!("{}: solved={}", input, outcome.is_solved());
+                Entry { input, outcome }
+            })
+            .collect()
+    });
+
+    Ok(Report { entries })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process};
+
+    use solver;
+
+    #[test]
+    fn guarded_solved() {
+        let day1 = solver(2017, 1).unwrap();
+        let outcome = solve_guarded(day1, "1122", &Overrides::new());
+        assert_eq!(outcome, Outcome::Solved(Answers::new(3, 0)));
+    }
+
+    #[test]
+    fn guarded_failed() {
+        let day1 = solver(2017, 1).unwrap();
+        let outcome = solve_guarded(day1, "12a", &Overrides::new());
+        assert_eq!(outcome, Outcome::Failed("unexpected token".to_owned()));
+    }
+
+    #[test]
+    fn guarded_panicked() {
+        fn explode(_: &str, _: &Overrides) -> Result<Answers> {
+            panic!("boom")
+        }
+
+        assert_eq!(
+            solve_guarded(explode, "", &Overrides::new()),
+            Outcome::Panicked("boom".to_owned())
+        );
+    }
+
+    #[test]
+    fn timed_out() {
+        fn forever(_: &str, _: &Overrides) -> Result<Answers> {
+            loop {
+                thread::sleep(Duration::from_secs(1));
+            }
+        }
+
+        let timeout = Some(Duration::from_millis(10));
+        let outcome = solve_timed(forever, "", &Overrides::new(), timeout);
+        assert_eq!(outcome, Outcome::TimedOut(0));
+    }
+
+    #[test]
+    fn run_directory() {
+        let dir = env::temp_dir().join(format!("adventofcode-batch-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a"), "1212").unwrap();
+        fs::write(dir.join("b"), "12x").unwrap();
+
+        let day1 = solver(2017, 1).unwrap();
+        let report = run(day1, &dir, &Overrides::new(), None).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.len(), 2);
+        assert_eq!(report.failures(), 1);
+        assert_eq!(
+            report.to_string(),
+            "input  part 1  part 2\n\
+             a      0       6\n\
+             b      ERROR: unexpected token\n"
+        );
+    }
+}
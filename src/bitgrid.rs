@@ -0,0 +1,377 @@
+//! A dense rectangle of booleans, packed 64 to a word.
+//!
+//! Each row starts on a word of its own, and the bits past the end of a row
+//! are always clear, so whole grids compare, hash and count word by word.
+
+use std::fmt;
+
+use geom::Point;
+use grid::Grid;
+use Result;
+
+const BITS: usize = 64;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    /// Words per row
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// A grid with every cell clear.
+    pub fn new(width: usize, height: usize) -> BitGrid {
+        let stride = (width + BITS - 1) / BITS;
+        BitGrid {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+        }
+    }
+
+    /// A grid with each cell computed from its position.
+    pub fn from_fn<F: FnMut(Point) -> bool>(width: usize, height: usize, mut f: F) -> BitGrid {
+        let mut grid = BitGrid::new(width, height);
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let p = Point::new(x, y);
+                if f(p) {
+                    grid.set(p, true);
+                }
+            }
+        }
+
+        grid
+    }
+
+    /// A grid from its rows, which must all have the same length.
+    pub fn from_rows<R: IntoIterator<Item = bool>>(rows: Vec<R>) -> Result<BitGrid> {
+        let rows: Vec<Vec<bool>> = rows.into_iter().map(|r| r.into_iter().collect()).collect();
+        let grid = Grid::from_rows(rows)?;
+        Ok(BitGrid::from_grid(&grid))
+    }
+
+    /// Parses a character map, with `f` telling which characters are set.
+    pub fn from_chars<F>(s: &str, f: F) -> Result<BitGrid>
+    where
+        F: FnMut(char) -> Result<bool>,
+    {
+        Ok(BitGrid::from_grid(&Grid::from_chars(s, f)?))
+    }
+
+    pub fn from_grid(grid: &Grid<bool>) -> BitGrid {
+        BitGrid::from_fn(grid.width(), grid.height(), |p| grid[p])
+    }
+
+    pub fn to_grid(&self) -> Grid<bool> {
+        Grid::from_fn(self.width, self.height, |p| self.get(p))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+    }
+
+    /// The word holding `p` and the mask of its bit, which must be inside.
+    fn locate(&self, p: Point) -> (usize, u64) {
+        let (x, y) = (p.x as usize, p.y as usize);
+        (y * self.stride + x / BITS, 1 << (x % BITS))
+    }
+
+    /// Whether `p` is set, which it never is outside the grid.
+    pub fn get(&self, p: Point) -> bool {
+        if !self.contains(p) {
+            return false;
+        }
+
+        let (i, mask) = self.locate(p);
+        self.words[i] & mask != 0
+    }
+
+    pub fn set(&mut self, p: Point, value: bool) {
+        assert!(
+            self.contains(p),
+            "{:?} is outside the {}x{} grid",
+            p,
+            self.width,
+            self.height
+        );
+
+        let (i, mask) = self.locate(p);
+        if value {
+            self.words[i] |= mask;
+        } else {
+            self.words[i] &= !mask;
+        }
+    }
+
+    /// The number of set cells.
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// The set cells, row by row.
+    pub fn ones<'a>(&'a self) -> impl Iterator<Item = Point> + 'a {
+        let stride = self.stride;
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let (y, base) = ((i / stride) as i32, (i % stride * BITS) as i32);
+            Ones(word).map(move |bit| Point::new(base + bit as i32, y))
+        })
+    }
+
+    /// The first set cell, row by row.
+    fn first(&self) -> Option<Point> {
+        self.ones().next()
+    }
+
+    /// Clears every cell that is set in `other`, which has the same shape.
+    pub fn subtract(&mut self, other: &BitGrid) {
+        assert_eq!((self.width, self.height), (other.width, other.height));
+        for (word, &mask) in self.words.iter_mut().zip(&other.words) {
+            *word &= !mask;
+        }
+    }
+
+    /// Moves every cell of row `y` `n` places to the right, wrapping around.
+    pub fn shift_row(&mut self, y: usize, n: usize) {
+        let w = self.width;
+        if w == 0 {
+            return;
+        }
+
+        let row: Vec<bool> = (0..w)
+            .map(|x| self.get(Point::new(x as i32, y as i32)))
+            .collect();
+        for (x, &value) in row.iter().enumerate() {
+            self.set(Point::new(((x + n) % w) as i32, y as i32), value);
+        }
+    }
+
+    /// Moves every cell of column `x` `n` places down, wrapping around.
+    pub fn shift_column(&mut self, x: usize, n: usize) {
+        let h = self.height;
+        if h == 0 {
+            return;
+        }
+
+        let column: Vec<bool> = (0..h)
+            .map(|y| self.get(Point::new(x as i32, y as i32)))
+            .collect();
+        for (y, &value) in column.iter().enumerate() {
+            self.set(Point::new(x as i32, ((y + n) % h) as i32), value);
+        }
+    }
+
+    /// The set cells connected to `start` through set cells sharing an edge,
+    /// or nothing if `start` itself is clear.
+    pub fn flood_fill(&self, start: Point) -> BitGrid {
+        let mut region = BitGrid::new(self.width, self.height);
+        if !self.get(start) {
+            return region;
+        }
+
+        region.set(start, true);
+        let mut stack = vec![start];
+        while let Some(p) = stack.pop() {
+            for q in p.neighbours4() {
+                if self.get(q) && !region.get(q) {
+                    region.set(q, true);
+                    stack.push(q);
+                }
+            }
+        }
+
+        region
+    }
+
+    /// The groups of set cells connected through shared edges.
+    pub fn regions(&self) -> Vec<BitGrid> {
+        let mut left = self.clone();
+        let mut regions = Vec::new();
+
+        while let Some(start) = left.first() {
+            let region = left.flood_fill(start);
+            left.subtract(&region);
+            regions.push(region);
+        }
+
+        regions
+    }
+
+    /// The `width` by `height` part of the grid starting at `origin`.
+    pub fn subgrid(&self, origin: Point, width: usize, height: usize) -> BitGrid {
+        BitGrid::from_fn(width, height, |p| self.get(origin + p))
+    }
+
+    /// Copies all of `other` into the grid, with its corner at `origin`.
+    pub fn paste(&mut self, origin: Point, other: &BitGrid) {
+        for y in 0..other.height as i32 {
+            for x in 0..other.width as i32 {
+                let p = Point::new(x, y);
+                self.set(origin + p, other.get(p));
+            }
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal.
+    pub fn transpose(&self) -> BitGrid {
+        BitGrid::from_fn(self.height, self.width, |p| self.get(Point::new(p.y, p.x)))
+    }
+
+    /// Rotates the grid a quarter turn clockwise.
+    pub fn rotate_right(&self) -> BitGrid {
+        let h = self.height as i32;
+        BitGrid::from_fn(self.height, self.width, |p| {
+            self.get(Point::new(p.y, h - 1 - p.x))
+        })
+    }
+
+    /// Rotates the grid a quarter turn anticlockwise.
+    pub fn rotate_left(&self) -> BitGrid {
+        let w = self.width as i32;
+        BitGrid::from_fn(self.height, self.width, |p| {
+            self.get(Point::new(w - 1 - p.y, p.x))
+        })
+    }
+
+    /// Swaps the left and right sides.
+    pub fn flip_horizontal(&self) -> BitGrid {
+        let w = self.width as i32;
+        BitGrid::from_fn(self.width, self.height, |p| {
+            self.get(Point::new(w - 1 - p.x, p.y))
+        })
+    }
+
+    /// Swaps the top and bottom, a whole row at a time.
+    pub fn flip_vertical(&self) -> BitGrid {
+        let mut words = Vec::with_capacity(self.words.len());
+        for row in self.words.chunks(self.stride.max(1)).rev() {
+            words.extend_from_slice(row);
+        }
+
+        BitGrid {
+            words,
+            ..self.clone()
+        }
+    }
+}
+
+/// The positions of the set bits of a word, lowest first.
+struct Ones(u64);
+
+impl Iterator for Ones {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let bit = self.0.trailing_zeros();
+        self.0 &= self.0 - 1;
+        Some(bit)
+    }
+}
+
+/// `#` for set cells and `.` for the others, one row per line.
+impl fmt::Display for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height as i32 {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for x in 0..self.width as i32 {
+                let c = if self.get(Point::new(x, y)) { '#' } else { '.' };
+                write!(f, "{}", c)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(s: &str) -> BitGrid {
+        BitGrid::from_chars(s, |c| Ok(c == '#')).unwrap()
+    }
+
+    #[test]
+    fn cells() {
+        let mut grid = BitGrid::new(130, 2);
+        grid.set(Point::new(0, 0), true);
+        grid.set(Point::new(64, 0), true);
+        grid.set(Point::new(129, 1), true);
+        assert_eq!(grid.count(), 3);
+        assert!(grid.get(Point::new(129, 1)));
+        assert!(!grid.get(Point::new(130, 1)));
+        assert!(!grid.get(Point::new(-1, 0)));
+
+        let ones: Vec<Point> = grid.ones().collect();
+        assert_eq!(
+            ones,
+            vec![Point::new(0, 0), Point::new(64, 0), Point::new(129, 1)]
+        );
+
+        grid.set(Point::new(64, 0), false);
+        assert_eq!(grid.count(), 2);
+        assert_eq!(BitGrid::from_grid(&grid.to_grid()), grid);
+        assert!(BitGrid::from_rows(vec![vec![true], vec![]]).is_err());
+    }
+
+    #[test]
+    fn shifts() {
+        let mut grid = bits("###....\n.......\n.......");
+        grid.shift_column(1, 1);
+        assert_eq!(grid.to_string(), "#.#....\n.#.....\n.......");
+        grid.shift_row(0, 4);
+        assert_eq!(grid.to_string(), "....#.#\n.#.....\n.......");
+        grid.shift_column(6, 4);
+        assert_eq!(grid.to_string(), "....#..\n.#....#\n.......");
+    }
+
+    #[test]
+    fn regions() {
+        let grid = bits("##.#\n#..#\n..##\n#...");
+        assert_eq!(
+            grid.flood_fill(Point::new(3, 0)),
+            bits("...#\n...#\n..##\n....")
+        );
+        assert!(grid.flood_fill(Point::new(2, 0)).is_empty());
+
+        let sizes: Vec<usize> = grid.regions().iter().map(BitGrid::count).collect();
+        assert_eq!(sizes, vec![3, 4, 1]);
+    }
+
+    #[test]
+    fn rotations_and_flips() {
+        let grid = bits("##.\n..#");
+        assert_eq!(grid.rotate_right(), bits(".#\n.#\n#."));
+        assert_eq!(grid.rotate_left(), bits(".#\n#.\n#."));
+        assert_eq!(grid.flip_horizontal(), bits(".##\n#.."));
+        assert_eq!(grid.flip_vertical(), bits("..#\n##."));
+        assert_eq!(grid.rotate_right(), grid.transpose().flip_horizontal());
+        assert_eq!(grid.subgrid(Point::new(1, 0), 2, 2), bits("#.\n.#"));
+
+        let mut big = BitGrid::new(4, 4);
+        big.paste(Point::new(2, 2), &grid.subgrid(Point::new(1, 0), 2, 2));
+        assert_eq!(big.to_string(), "....\n....\n..#.\n...#");
+    }
+}
@@ -0,0 +1,174 @@
+use std::fmt;
+
+use solver;
+
+/// A puzzle of the event, and how far along this crate is with it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Puzzle {
+    pub edition: u32,
+    pub day: u32,
+    pub title: &'static str,
+    /// Has unit tests checking the answers
+    pub tested: bool,
+    /// Has criterion benchmarks in `benches/`
+    pub benchmarked: bool,
+}
+
+impl Puzzle {
+    pub fn implemented(&self) -> bool {
+        solver(self.edition, self.day).is_ok()
+    }
+}
+
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} day {} ({})", self.edition, self.day, self.title)
+    }
+}
+
+macro_rules! puzzles {
+    ($($edition:expr, $day:expr, $title:expr, $tested:expr, $benchmarked:expr;)*) => {
+        &[$(Puzzle {
+            edition: $edition,
+            day: $day,
+            title: $title,
+            tested: $tested,
+            benchmarked: $benchmarked,
+        }),*]
+    };
+}
+
+pub const PUZZLES: &[Puzzle] = puzzles! {
+    2016, 1, "No Time for a Taxicab", true, false;
+    2016, 2, "Bathroom Security", true, false;
+    2016, 3, "Squares With Three Sides", true, false;
+    2016, 4, "Security Through Obscurity", true, false;
+    2016, 5, "How About a Nice Game of Chess?", true, false;
+    2016, 6, "Signals and Noise", true, false;
+    2016, 7, "Internet Protocol Version 7", true, false;
+    2016, 8, "Two-Factor Authentication", false, false;
+    2016, 9, "Explosives in Cyberspace", false, false;
+    2016, 10, "Balance Bots", false, false;
+    2016, 11, "Radioisotope Thermoelectric Generators", false, false;
+    2016, 12, "Leonardo's Monorail", false, false;
+    2016, 13, "A Maze of Twisty Little Cubicles", false, false;
+    2016, 14, "One-Time Pad", false, false;
+    2016, 15, "Timing is Everything", false, false;
+    2016, 16, "Dragon Checksum", false, false;
+    2016, 17, "Two Steps Forward", false, false;
+    2016, 18, "Like a Rogue", false, false;
+    2016, 19, "An Elephant Named Joseph", false, false;
+    2016, 20, "Firewall Rules", false, false;
+    2016, 21, "Scrambled Letters and Hash", false, false;
+    2016, 22, "Grid Computing", false, false;
+    2016, 23, "Safe Cracking", false, false;
+    2016, 24, "Air Duct Spelunking", false, false;
+    2016, 25, "Clock Signal", false, false;
+    2017, 1, "Inverse Captcha", true, true;
+    2017, 2, "Corruption Checksum", true, true;
+    2017, 3, "Spiral Memory", true, true;
+    2017, 4, "High-Entropy Passphrases", true, true;
+    2017, 5, "A Maze of Twisty Trampolines, All Alike", true, true;
+    2017, 6, "Memory Reallocation", true, true;
+    2017, 7, "Recursive Circus", true, true;
+    2017, 8, "I Heard You Like Registers", true, true;
+    2017, 9, "Stream Processing", true, true;
+    2017, 10, "Knot Hash", true, true;
+    2017, 11, "Hex Ed", true, true;
+    2017, 12, "Digital Plumber", true, true;
+    2017, 13, "Packet Scanners", true, true;
+    2017, 14, "Disk Defragmentation", true, true;
+    2017, 15, "Dueling Generators", true, true;
+    2017, 16, "Permutation Promenade", true, true;
+    2017, 17, "Spinlock", true, true;
+    2017, 18, "Duet", true, true;
+    2017, 19, "A Series of Tubes", true, true;
+    2017, 20, "Particle Swarm", true, true;
+    2017, 21, "Fractal Art", true, true;
+    2017, 22, "Sporifica Virus", true, true;
+    2017, 23, "Coprocessor Conflagration", true, true;
+    2017, 24, "Electromagnetic Moat", true, true;
+    2017, 25, "The Halting Problem", true, true;
+};
+
+pub fn find(edition: u32, day: u32) -> Option<&'static Puzzle> {
+    PUZZLES
+        .iter()
+        .find(|p| p.edition == edition && p.day == day)
+}
+
+/// The puzzles of one edition, or of all of them.
+pub fn list(edition: Option<u32>) -> Vec<&'static Puzzle> {
+    PUZZLES
+        .iter()
+        .filter(|p| edition.map_or(true, |e| p.edition == e))
+        .collect()
+}
+
+/// Formats puzzles as a table, with a mark for every kind of progress.
+pub struct Table<'a>(pub &'a [&'static Puzzle]);
+
+impl<'a> fmt::Display for Table<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn mark(done: bool) -> &'static str {
+            if done {
+                "yes"
+            } else {
+                "-"
+            }
+        }
+
+        let width = self.0.iter().map(|p| p.title.len()).max().unwrap_or(0);
+
+        writeln!(
+            f,
+            "edition  day  {:<w$}  solver  tests  bench",
+            "title",
+            w = width
+        )?;
+
+        for puzzle in self.0 {
+            writeln!(
+                f,
+                "{:<7}  {:>3}  {:<w$}  {:<6}  {:<5}  {}",
+                puzzle.edition,
+                puzzle.day,
+                puzzle.title,
+                mark(puzzle.implemented()),
+                mark(puzzle.tested),
+                mark(puzzle.benchmarked),
+                w = width
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete() {
+        for edition in 2016..2018 {
+            let days: Vec<u32> = list(Some(edition)).iter().map(|p| p.day).collect();
+            assert_eq!(days, (1..26).collect::<Vec<u32>>());
+        }
+    }
+
+    #[test]
+    fn progress_needs_a_solver() {
+        for puzzle in PUZZLES {
+            if puzzle.tested || puzzle.benchmarked {
+                assert!(puzzle.implemented(), "{} has no solver", puzzle);
+            }
+        }
+    }
+
+    #[test]
+    fn lookup() {
+        assert_eq!(find(2017, 21).map(|p| p.title), Some("Fractal Art"));
+        assert_eq!(find(2017, 26), None);
+    }
+}
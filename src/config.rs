@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use failure::Error;
+use toml::{self, value::Table};
+
+use Result;
+
+/// The file that is read when no configuration file is given explicitly.
+pub const DEFAULT_PATH: &str = "aoc.toml";
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl Default for Format {
+    fn default() -> Format {
+        Format::Text
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Format> {
+        let format = match s {
+            "text" => Format::Text,
+            "json" => Format::Json,
+            other => bail!("unknown output format: {}", other),
+        };
+
+        Ok(format)
+    }
+}
+
+/// Project defaults, so they don't have to be repeated on every invocation.
+/// Anything given on the command line takes precedence.
+///
+/// ```toml
+/// edition = 2017
+/// data_dir = "inputs"
+/// format = "json"
+/// timeout = 60
+/// session = ".session"
+///
+/// [params.2017.22]
+/// part1_bursts = 70
+/// part2_bursts = 100
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The edition used when only a day is given
+    pub edition: Option<u32>,
+    /// Directory holding puzzle inputs as `<data_dir>/<edition>/<day>`
+    pub data_dir: Option<PathBuf>,
+    pub format: Option<Format>,
+    /// Seconds a solver may run before it is abandoned
+    pub timeout: Option<u64>,
+    /// File containing the adventofcode.com session token
+    pub session: Option<PathBuf>,
+    /// Parameter overrides, keyed by edition and then by day
+    pub params: BTreeMap<String, BTreeMap<String, Table>>,
+}
+
+impl Config {
+    pub fn parse(s: &str) -> Result<Config> {
+        toml::from_str(s).map_err(Into::into)
+    }
+
+    /// Reads the configuration at `path`, or at `DEFAULT_PATH` if it exists.
+    pub fn load(path: Option<&Path>) -> Result<Config> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None if Path::new(DEFAULT_PATH).is_file() => PathBuf::from(DEFAULT_PATH),
+            None => return Ok(Config::default()),
+        };
+
+        info!("reading configuration from {}", path.display());
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| format_err!("failed to read {}: {}", path.display(), e))?;
+
+        Config::parse(&contents).map_err(|e| format_err!("invalid {}: {}", path.display(), e))
+    }
+
+    pub fn params(&self, edition: u32, day: u32) -> Option<&Table> {
+        self.params
+            .get(&edition.to_string())
+            .and_then(|days| days.get(&day.to_string()))
+    }
+
+    /// The session token, read from the `session` file without the
+    /// whitespace around it.
+    pub fn session_token(&self) -> Result<Option<String>> {
+        match self.session {
+            Some(ref path) => {
+                let token = fs::read_to_string(path)
+                    .map_err(|e| format_err!("failed to read {}: {}", path.display(), e))?;
+                Ok(Some(token.trim().to_owned()))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use params::Overrides;
+    use seventeen::day22;
+    use std::{env, process};
+    use toml::Value;
+
+    #[test]
+    fn empty_config() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn full_config() {
+        let config = Config::parse(
+            "edition = 2017\ndata_dir = \"inputs\"\nformat = \"json\"\ntimeout = 60\nsession = \
+             \".session\"\n\n[params.2017.22]\npart1_bursts = 70\npart2_bursts = 100\n",
+        ).unwrap();
+
+        assert_eq!(config.edition, Some(2017));
+        assert_eq!(config.data_dir, Some(PathBuf::from("inputs")));
+        assert_eq!(config.format, Some(Format::Json));
+        assert_eq!(config.timeout, Some(60));
+        assert_eq!(config.session, Some(PathBuf::from(".session")));
+        assert_eq!(
+            config.params(2017, 22).and_then(|p| p.get("part1_bursts")),
+            Some(&Value::Integer(70))
+        );
+        assert_eq!(config.params(2017, 21), None);
+
+        let overrides = Overrides::from_table(config.params(2017, 22).unwrap());
+        let params: day22::Params = overrides.apply().unwrap();
+        assert_eq!((params.part1_bursts, params.part2_bursts), (70, 100));
+    }
+
+    #[test]
+    fn session_token() {
+        assert_eq!(Config::default().session_token().unwrap(), None);
+
+        let path = env::temp_dir().join(format!("adventofcode-session-{}", process::id()));
+        fs::write(&path, "  53616c7465645f5f\n").unwrap();
+        let config = Config {
+            session: Some(path.clone()),
+            ..Config::default()
+        };
+        let token = config.session_token();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(token.unwrap(), Some("53616c7465645f5f".to_owned()));
+
+        let missing = Config {
+            session: Some(PathBuf::from("/nonexistent/.session")),
+            ..Config::default()
+        };
+        assert!(missing.session_token().is_err());
+    }
+
+    #[test]
+    fn unknown_key() {
+        assert!(Config::parse("editon = 2017").is_err());
+    }
+
+    #[test]
+    fn unknown_format() {
+        assert!(Config::parse("format = \"xml\"").is_err());
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
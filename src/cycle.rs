@@ -0,0 +1,170 @@
+//! Finding where a sequence of states starts repeating itself.
+//!
+//! The sequence is given by its first state and a function computing each
+//! state from the one before, so it must eventually repeat if there are only
+//! finitely many states.
+
+use std::hash::Hash;
+
+use fnv::FnvHashMap;
+
+/// The first `start` states of a sequence are never seen again; from there
+/// on it repeats every `length` steps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// Number of steps to the first state on the cycle, usually called mu
+    pub start: usize,
+    /// Number of states on the cycle, usually called lambda
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Brent's algorithm, which only ever keeps two states around but computes
+/// some of them more than once.
+pub fn brent<T, F>(init: T, mut step: F) -> Cycle
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T) -> T,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = init.clone();
+    let mut hare = step(&init);
+
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = init.clone();
+    let mut hare = init;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// The states up to step `n`, or up to the first repeated one if that comes
+/// sooner.
+fn history<T, F>(init: T, step: &mut F, n: usize) -> (Vec<T>, Option<Cycle>)
+where
+    T: Clone + Eq + Hash,
+    F: FnMut(&T) -> T,
+{
+    let mut seen = FnvHashMap::default();
+    let mut states = vec![init];
+
+    for i in 0..n {
+        seen.insert(states[i].clone(), i);
+
+        let next = step(&states[i]);
+        if let Some(&start) = seen.get(&next) {
+            let cycle = Cycle {
+                start,
+                length: i + 1 - start,
+            };
+            return (states, Some(cycle));
+        }
+
+        states.push(next);
+    }
+
+    (states, None)
+}
+
+/// Remembers every state, so each one is only computed once.
+pub fn hashing<T, F>(init: T, mut step: F) -> Cycle
+where
+    T: Clone + Eq + Hash,
+    F: FnMut(&T) -> T,
+{
+    match history(init, &mut step, usize::max_value()) {
+        (_, Some(cycle)) => cycle,
+        (states, None) => panic!("no state repeated in {} steps", states.len()),
+    }
+}
+
+/// The state after `n` steps, skipping all complete trips around the cycle.
+pub fn nth<T, F>(init: T, mut step: F, n: usize) -> T
+where
+    T: Clone + Eq + Hash,
+    F: FnMut(&T) -> T,
+{
+    let (mut states, cycle) = history(init, &mut step, n);
+    let i = cycle.map_or(n, |c| c.reduce(n));
+    states.swap_remove(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 2 -> ...
+    fn next(&i: &usize) -> usize {
+        [1, 2, 3, 4, 2][i]
+    }
+
+    #[test]
+    fn cycles() {
+        let expected = Cycle {
+            start: 2,
+            length: 3,
+        };
+        assert_eq!(brent(0, next), expected);
+        assert_eq!(hashing(0, next), expected);
+        assert_eq!(
+            hashing(3, next),
+            Cycle {
+                start: 0,
+                length: 3
+            }
+        );
+    }
+
+    #[test]
+    fn algorithms_agree() {
+        let step = |&x: &u64| (x * x + 1) % 1_000_003;
+        for init in 0..20 {
+            assert_eq!(brent(init, step), hashing(init, step));
+        }
+    }
+
+    #[test]
+    fn nth_state() {
+        assert_eq!(nth(0, next, 1), 1);
+        assert_eq!(nth(0, next, 10), 4);
+        assert_eq!(nth(0, next, 1_000_000_000), 4);
+
+        let mut steps = 0;
+        let count = |&x: &u32| {
+            steps += 1;
+            x + 1
+        };
+        let last = nth(0, count, 5);
+        assert_eq!((last, steps), (5, 5));
+    }
+}
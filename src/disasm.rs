@@ -0,0 +1,249 @@
+//! Listings of register machine programs, with the relative jumps resolved
+//! to labels and the code split into basic blocks.
+//!
+//! Everything a listing adds to the instructions is a comment, so it parses
+//! back into the same program.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::ops::Range;
+
+use missing;
+use vm::{Inst, Operand};
+use Result;
+
+/// How wide instructions are padded to line up the comments after them.
+const COLUMN: usize = 20;
+
+/// Where a jump goes when it is taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    Addr(usize),
+    /// Outside the program, which ends it
+    Exit,
+    /// Wherever a register says
+    Unknown,
+}
+
+impl Target {
+    /// The target of the instruction at `addr`, if it is a jump.
+    fn of(program: &[Inst], addr: usize) -> Option<Target> {
+        let target = match program[addr].jump_offset()? {
+            Operand::Val(offset) => {
+                let to = addr as i64 + offset;
+                if to < 0 || to >= program.len() as i64 {
+                    Target::Exit
+                } else {
+                    Target::Addr(to as usize)
+                }
+            }
+            Operand::Reg(_) => Target::Unknown,
+        };
+
+        Some(target)
+    }
+}
+
+pub struct Listing<'a> {
+    program: &'a [Inst],
+    targets: Vec<Option<Target>>,
+    /// The number of the label of every address that is jumped to
+    labels: BTreeMap<usize, usize>,
+    blocks: Vec<Range<usize>>,
+}
+
+impl<'a> Listing<'a> {
+    pub fn new(program: &'a [Inst]) -> Listing<'a> {
+        let targets: Vec<Option<Target>> = (0..program.len())
+            .map(|addr| Target::of(program, addr))
+            .collect();
+
+        let mut labels = BTreeMap::new();
+        for target in &targets {
+            if let Some(Target::Addr(to)) = *target {
+                labels.insert(to, 0);
+            }
+        }
+        for (n, label) in labels.values_mut().enumerate() {
+            *label = n;
+        }
+
+        // Blocks start the program, at labels and after jumps.
+        let mut starts: BTreeSet<usize> = labels.keys().cloned().collect();
+        starts.insert(0);
+        for (addr, target) in targets.iter().enumerate() {
+            if target.is_some() {
+                starts.insert(addr + 1);
+            }
+        }
+
+        let starts: Vec<usize> = starts
+            .into_iter()
+            .filter(|&addr| addr < program.len())
+            .collect();
+        let blocks = starts
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| start..starts.get(i + 1).cloned().unwrap_or(program.len()))
+            .collect();
+
+        Listing {
+            program,
+            targets,
+            labels,
+            blocks,
+        }
+    }
+
+    /// The addresses of each basic block, in order.
+    pub fn blocks(&self) -> &[Range<usize>] {
+        &self.blocks
+    }
+
+    /// Where the instruction at `addr` jumps, if it is a jump.
+    pub fn target(&self, addr: usize) -> Option<Target> {
+        self.targets[addr]
+    }
+
+    /// The number of the label at `addr`, if anything jumps there.
+    pub fn label(&self, addr: usize) -> Option<usize> {
+        self.labels.get(&addr).cloned()
+    }
+
+    /// The jumps to `addr` from itself or further on, which make it the
+    /// header of a loop.
+    pub fn back_edges(&self, addr: usize) -> Vec<usize> {
+        (addr..self.program.len())
+            .filter(|&from| self.targets[from] == Some(Target::Addr(addr)))
+            .collect()
+    }
+
+    fn name(&self, target: Target) -> String {
+        match target {
+            Target::Addr(to) => format!("L{}", self.labels[&to]),
+            Target::Exit => "end".to_string(),
+            Target::Unknown => "?".to_string(),
+        }
+    }
+}
+
+/// Each block after a comment with its address, label and back edges, and
+/// each jump followed by the label it goes to.
+impl<'a> fmt::Display for Listing<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, block) in self.blocks.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "# {}", block.start)?;
+            if let Some(label) = self.label(block.start) {
+                write!(f, " L{}", label)?;
+            }
+            let back: Vec<String> = self
+                .back_edges(block.start)
+                .iter()
+                .map(usize::to_string)
+                .collect();
+            if !back.is_empty() {
+                write!(f, ", loop back from {}", back.join(", "))?;
+            }
+            writeln!(f)?;
+
+            for addr in block.clone() {
+                let inst = self.program[addr].to_string();
+                match self.targets[addr] {
+                    Some(target) => {
+                        writeln!(f, "    {:<2$}# to {}", inst, self.name(target), COLUMN)?
+                    }
+                    None => writeln!(f, "    {}", inst)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the program that is the input of a day.
+pub type Parser = fn(&str) -> Result<Vec<Inst>>;
+
+pub fn parser(edition: u32, day: u32) -> Option<Parser> {
+    use seventeen;
+
+    let parser: Parser = match (edition, day) {
+        (2017, 18) => seventeen::day18::parse,
+        (2017, 23) => seventeen::day23::parse_inst,
+        _ => return None,
+    };
+
+    Some(parser)
+}
+
+/// The listing of the program that is the input of a day.
+pub fn disassemble(edition: u32, day: u32, input: &str) -> Result<String> {
+    match parser(edition, day) {
+        Some(parse) => Ok(Listing::new(&parse(input)?).to_string()),
+        None => Err(missing("program to disassemble", edition, day)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vm;
+
+    const PROGRAM: &str = "set i 3\nadd s i\nsub i 1\njnz i -2\njgz s 2\nsnd s\njgz 1 b";
+    const ALL: &[&str] = &["set", "add", "sub", "jnz", "jgz", "snd"];
+
+    #[test]
+    fn blocks() {
+        let program = vm::parse(PROGRAM, ALL).unwrap();
+        let listing = Listing::new(&program);
+        assert_eq!(
+            listing.blocks().to_vec(),
+            vec![0..1, 1..4, 4..5, 5..6, 6..7]
+        );
+        assert_eq!(listing.target(3), Some(Target::Addr(1)));
+        assert_eq!(listing.target(4), Some(Target::Addr(6)));
+        assert_eq!(listing.target(6), Some(Target::Unknown));
+        assert_eq!(listing.target(0), None);
+        assert_eq!((listing.label(1), listing.label(6)), (Some(0), Some(1)));
+        assert_eq!(listing.back_edges(1), vec![3]);
+        assert!(listing.back_edges(6).is_empty());
+
+        let program = vm::parse("add a 1\njnz 1 -1\njnz 1 2", ALL).unwrap();
+        let listing = Listing::new(&program);
+        assert_eq!(listing.target(2), Some(Target::Exit));
+        assert_eq!(listing.back_edges(0), vec![1]);
+    }
+
+    #[test]
+    fn listing() {
+        let program = vm::parse(PROGRAM, ALL).unwrap();
+        let listing = Listing::new(&program).to_string();
+        assert_eq!(
+            listing,
+            "# 0\n    set i 3\n\n\
+             # 1 L0, loop back from 3\n    add s i\n    sub i 1\n    jnz i -2            # to L0\n\n\
+             # 4\n    jgz s 2             # to L1\n\n\
+             # 5\n    snd s\n\n\
+             # 6 L1\n    jgz 1 b             # to ?\n"
+        );
+        assert_eq!(vm::parse(&listing, ALL).unwrap(), program);
+    }
+
+    #[test]
+    fn round_trip() {
+        for &(day, input) in &[
+            (18, include_str!("../data/d18-test")),
+            (23, include_str!("../data/d23-test")),
+        ] {
+            let parse = parser(2017, day).unwrap();
+            let listing = disassemble(2017, day, input).unwrap();
+            assert_eq!(parse(&listing).unwrap(), parse(input).unwrap());
+        }
+
+        assert!(disassemble(2017, 1, "").is_err());
+    }
+}
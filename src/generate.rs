@@ -0,0 +1,58 @@
+use rand::prng::XorShiftRng;
+
+use {missing, seeded_rng, Result};
+
+/// Produces a valid random input of roughly the given size, where what counts
+/// as size depends on the day, e.g. programs in a tower or lines of code.
+pub type Generator = fn(&mut XorShiftRng, usize) -> String;
+
+pub fn generator(edition: u32, day: u32) -> Option<Generator> {
+    let generator: Generator = match (edition, day) {
+        (2016, 4) => ::sixteen::day4::generate,
+        (2017, 7) => ::seventeen::day7::generate,
+        (2017, 12) => ::seventeen::day12::generate,
+        (2017, 18) => ::seventeen::day18::generate,
+        (2017, 24) => ::seventeen::day24::generate,
+        _ => return None,
+    };
+
+    Some(generator)
+}
+
+/// Generates an input for a day, the same one every time for the same seed.
+pub fn generate(edition: u32, day: u32, size: usize, seed: u64) -> Result<String> {
+    match generator(edition, day) {
+        Some(generate) => Ok(generate(&mut seeded_rng(seed), size)),
+        None => Err(missing("input generator", edition, day)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use params::Overrides;
+    use solver;
+
+    #[test]
+    fn deterministic() {
+        assert_eq!(
+            generate(2017, 12, 50, 3).unwrap(),
+            generate(2017, 12, 50, 3).unwrap()
+        );
+        assert!(generate(2017, 1, 50, 3).is_err());
+    }
+
+    #[test]
+    fn solvable() {
+        for &(edition, day) in &[(2017, 7), (2017, 12), (2017, 18), (2017, 24)] {
+            let input = generate(edition, day, 15, 1).unwrap();
+            let solve = solver(edition, day).unwrap();
+            assert!(
+                solve(&input, &Overrides::new()).is_ok(),
+                "{} day {}",
+                edition,
+                day
+            );
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Points, directions and rotations on a square grid.
+//!
+//! Coordinates follow the usual convention for text input: `x` grows to the
+//! east (right) and `y` grows to the south (down), so North is `y - 1`.
+
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use self::Direction::{East, North, South, West};
+use self::Rotation::{Left, Right};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Rotation {
+    Left,
+    Right,
+}
+
+/// A compass direction, with North pointing up on the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// All directions, in clockwise order.
+    pub const ALL: [Direction; 4] = [North, East, South, West];
+
+    pub fn left(self) -> Direction {
+        match self {
+            North => West,
+            West => South,
+            South => East,
+            East => North,
+        }
+    }
+
+    pub fn right(self) -> Direction {
+        match self {
+            North => East,
+            East => South,
+            South => West,
+            West => North,
+        }
+    }
+
+    pub fn reverse(self) -> Direction {
+        self.right().right()
+    }
+
+    pub fn turn(self, r: Rotation) -> Direction {
+        match r {
+            Left => self.left(),
+            Right => self.right(),
+        }
+    }
+
+    /// The change in `(x, y)` of a single step in this direction.
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            North => (0, -1),
+            East => (1, 0),
+            South => (0, 1),
+            West => (-1, 0),
+        }
+    }
+}
+
+/// A point in the plane.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A point in space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3<T = i32> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point<T> {
+    pub fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+}
+
+impl<T: Default> Point<T> {
+    pub fn origin() -> Point<T> {
+        Point::default()
+    }
+}
+
+impl<T> Point3<T> {
+    pub fn new(x: T, y: T, z: T) -> Point3<T> {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Default> Point3<T> {
+    pub fn origin() -> Point3<T> {
+        Point3::default()
+    }
+}
+
+const NEIGHBOURS4: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+const NEIGHBOURS8: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {
+        $(
+            impl Point<$t> {
+                pub fn manhattan(self, other: Point<$t>) -> $t {
+                    (self.x - other.x).abs() + (self.y - other.y).abs()
+                }
+
+                /// The point `n` steps away in direction `d`.
+                pub fn step_n(self, d: Direction, n: $t) -> Point<$t> {
+                    let (dx, dy) = d.offset();
+                    Point::new(self.x + dx as $t * n, self.y + dy as $t * n)
+                }
+
+                pub fn step(self, d: Direction) -> Point<$t> {
+                    self.step_n(d, 1)
+                }
+
+                /// The points sharing an edge with this one, clockwise from North.
+                pub fn neighbours4(self) -> impl Iterator<Item = Point<$t>> {
+                    NEIGHBOURS4
+                        .iter()
+                        .map(move |&(dx, dy)| Point::new(self.x + dx as $t, self.y + dy as $t))
+                }
+
+                /// The points sharing an edge or a corner with this one,
+                /// clockwise from the North West.
+                pub fn neighbours8(self) -> impl Iterator<Item = Point<$t>> {
+                    NEIGHBOURS8
+                        .iter()
+                        .map(move |&(dx, dy)| Point::new(self.x + dx as $t, self.y + dy as $t))
+                }
+            }
+
+            impl Point3<$t> {
+                pub fn manhattan(self, other: Point3<$t>) -> $t {
+                    (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+                }
+
+                /// The points sharing a face with this one.
+                pub fn neighbours6(self) -> impl Iterator<Item = Point3<$t>> {
+                    let Point3 { x, y, z } = self;
+                    vec![
+                        Point3::new(x - 1, y, z),
+                        Point3::new(x + 1, y, z),
+                        Point3::new(x, y - 1, z),
+                        Point3::new(x, y + 1, z),
+                        Point3::new(x, y, z - 1),
+                        Point3::new(x, y, z + 1),
+                    ].into_iter()
+                }
+            }
+        )*
+    };
+}
+
+impl_signed!(i32, i64, isize);
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, rhs: Point<T>) -> Point<T> {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, rhs: Point<T>) -> Point<T> {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Point<T>;
+
+    fn neg(self) -> Point<T> {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, k: T) -> Point<T> {
+        Point::new(self.x * k, self.y * k)
+    }
+}
+
+impl<T: Add<Output = T> + Copy> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Point<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Sub<Output = T> + Copy> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Point<T>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Point3<T>;
+
+    fn add(self, rhs: Point3<T>) -> Point3<T> {
+        Point3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Point3<T>;
+
+    fn sub(self, rhs: Point3<T>) -> Point3<T> {
+        Point3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Add<Output = T> + Copy> AddAssign for Point3<T> {
+    fn add_assign(&mut self, rhs: Point3<T>) {
+        *self = *self + rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        assert_eq!(North.turn(Left), West);
+        assert_eq!(West.turn(Right), North);
+        assert_eq!(East.reverse(), West);
+        for (i, &d) in Direction::ALL.iter().enumerate() {
+            assert_eq!(d.left().right(), d);
+            assert_eq!(d.right(), Direction::ALL[(i + 1) % 4]);
+        }
+    }
+
+    #[test]
+    fn steps() {
+        let p: Point = Point::new(2, 3);
+        assert_eq!(p.step(North), Point::new(2, 2));
+        assert_eq!(p.step_n(West, 5), Point::new(-3, 3));
+        assert_eq!(p.step(South).step(North), p);
+        assert_eq!(p.manhattan(Point::origin()), 5);
+        assert_eq!(p + Point::new(1, 1) - Point::new(3, 4), Point::new(0, 0));
+    }
+
+    #[test]
+    fn neighbours() {
+        let p: Point<i64> = Point::new(0, 0);
+        assert_eq!(p.neighbours4().count(), 4);
+        assert!(p.neighbours4().all(|q| q.manhattan(p) == 1));
+        assert_eq!(p.neighbours8().count(), 8);
+        assert!(p
+            .neighbours8()
+            .all(|q| q != p && (q.x - p.x).abs() <= 1 && (q.y - p.y).abs() <= 1));
+
+        let q: Point3 = Point3::new(1, 2, 3);
+        assert!(q.neighbours6().all(|r| r.manhattan(q) == 1));
+        assert_eq!(q.manhattan(Point3::origin()), 6);
+    }
+}
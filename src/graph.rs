@@ -0,0 +1,301 @@
+//! Graphs, searching them and splitting them into connected components.
+
+use std::hash::Hash;
+use std::iter::FromIterator;
+
+use fnv::{FnvHashMap, FnvHashSet};
+
+use bitgrid::BitGrid;
+use geom::Point;
+use grid::Grid;
+use search;
+
+/// A graph given by the neighbours of each of its nodes.
+pub trait Graph {
+    type Node: Copy + Eq + Hash;
+
+    fn nodes(&self) -> Vec<Self::Node>;
+
+    fn neighbours(&self, node: Self::Node) -> Vec<Self::Node>;
+}
+
+/// A graph built edge by edge, with a list of neighbours for every node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Adjacency<N: Eq + Hash> {
+    neighbours: FnvHashMap<N, Vec<N>>,
+}
+
+impl<N: Copy + Eq + Hash> Adjacency<N> {
+    pub fn new() -> Adjacency<N> {
+        Adjacency::default()
+    }
+
+    pub fn add_node(&mut self, node: N) {
+        self.neighbours.entry(node).or_insert_with(Vec::new);
+    }
+
+    /// Adds an edge that can only be followed from `from` to `to`.
+    pub fn add_arc(&mut self, from: N, to: N) {
+        self.add_node(to);
+        self.neighbours
+            .entry(from)
+            .or_insert_with(Vec::new)
+            .push(to);
+    }
+
+    /// Adds an edge that can be followed both ways.
+    pub fn add_edge(&mut self, a: N, b: N) {
+        self.add_arc(a, b);
+        if a != b {
+            self.add_arc(b, a);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.neighbours.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.neighbours.is_empty()
+    }
+
+    /// Every edge, once in each direction it can be followed.
+    pub fn edges<'a>(&'a self) -> impl Iterator<Item = (N, N)> + 'a {
+        self.neighbours
+            .iter()
+            .flat_map(|(&a, bs)| bs.iter().map(move |&b| (a, b)))
+    }
+}
+
+impl<N: Eq + Hash> Default for Adjacency<N> {
+    fn default() -> Adjacency<N> {
+        Adjacency {
+            neighbours: FnvHashMap::default(),
+        }
+    }
+}
+
+/// Collects edges that can be followed both ways.
+impl<N: Copy + Eq + Hash> FromIterator<(N, N)> for Adjacency<N> {
+    fn from_iter<I: IntoIterator<Item = (N, N)>>(edges: I) -> Adjacency<N> {
+        let mut graph = Adjacency::new();
+        for (a, b) in edges {
+            graph.add_edge(a, b);
+        }
+
+        graph
+    }
+}
+
+impl<N: Copy + Eq + Hash> Graph for Adjacency<N> {
+    type Node = N;
+
+    fn nodes(&self) -> Vec<N> {
+        self.neighbours.keys().cloned().collect()
+    }
+
+    fn neighbours(&self, node: N) -> Vec<N> {
+        self.neighbours.get(&node).cloned().unwrap_or_default()
+    }
+}
+
+/// The set cells of a grid, each joined to the set cells sharing an edge
+/// with it.
+impl Graph for Grid<bool> {
+    type Node = Point;
+
+    fn nodes(&self) -> Vec<Point> {
+        self.iter()
+            .filter(|&(_, &set)| set)
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    fn neighbours(&self, node: Point) -> Vec<Point> {
+        node.neighbours4()
+            .filter(|&p| self.get(p) == Some(&true))
+            .collect()
+    }
+}
+
+/// The same as for `Grid<bool>`.
+impl Graph for BitGrid {
+    type Node = Point;
+
+    fn nodes(&self) -> Vec<Point> {
+        self.ones().collect()
+    }
+
+    fn neighbours(&self, node: Point) -> Vec<Point> {
+        node.neighbours4().filter(|&p| self.get(p)).collect()
+    }
+}
+
+/// The nodes reachable from `start` with their distance from it, closest
+/// first.
+pub fn bfs<G: Graph>(graph: &G, start: G::Node) -> Vec<(G::Node, usize)> {
+    search::reachable(start, |&node| graph.neighbours(node))
+}
+
+/// The nodes reachable from `start`, in depth-first order.
+pub fn dfs<G: Graph>(graph: &G, start: G::Node) -> Vec<G::Node> {
+    let mut seen = FnvHashSet::default();
+    let mut stack = vec![start];
+    let mut order = Vec::new();
+
+    while let Some(node) = stack.pop() {
+        if !seen.insert(node) {
+            continue;
+        }
+
+        order.push(node);
+        let neighbours = graph.neighbours(node);
+        stack.extend(neighbours.into_iter().rev().filter(|n| !seen.contains(n)));
+    }
+
+    order
+}
+
+/// The nodes of each connected component. Every edge must go both ways.
+pub fn components<G: Graph>(graph: &G) -> Vec<Vec<G::Node>> {
+    let mut seen = FnvHashSet::default();
+    let mut components = Vec::new();
+
+    for node in graph.nodes() {
+        if seen.contains(&node) {
+            continue;
+        }
+
+        let component = dfs(graph, node);
+        seen.extend(component.iter().cloned());
+        components.push(component);
+    }
+
+    components
+}
+
+/// The number of nodes in each connected component.
+pub fn component_sizes<G: Graph>(graph: &G) -> Vec<usize> {
+    components(graph).iter().map(Vec::len).collect()
+}
+
+/// Disjoint sets of the numbers `0..n`, merged by rank with path compression.
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    /// Every number in a set of its own.
+    pub fn new(n: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            size: vec![1; n],
+            count: n,
+        }
+    }
+
+    /// The representative of the set holding `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut x = x;
+        while self.parent[x] != root {
+            let next = self.parent[x];
+            self.parent[x] = root;
+            x = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets holding `a` and `b`, returning false if they were
+    /// already the same.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (root, child) = if self.rank[a] < self.rank[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        if self.rank[root] == self.rank[child] {
+            self.rank[root] += 1;
+        }
+
+        self.count -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The number of elements in the set holding `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// The number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_find() {
+        let mut sets = UnionFind::new(6);
+        assert_eq!(sets.count(), 6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.size(2), 4);
+        assert_eq!(sets.size(5), 1);
+        assert_eq!(sets.count(), 3);
+    }
+
+    #[test]
+    fn searches() {
+        let graph: Adjacency<u32> = vec![(0, 1), (1, 2), (0, 3), (3, 2), (4, 5)]
+            .into_iter()
+            .collect();
+        let distances: FnvHashMap<u32, usize> = bfs(&graph, 0).into_iter().collect();
+        assert_eq!(distances.len(), 4);
+        assert_eq!(distances[&2], 2);
+        assert_eq!(dfs(&graph, 5), vec![5, 4]);
+        assert_eq!(graph.edges().count(), 10);
+
+        let mut sizes = component_sizes(&graph);
+        sizes.sort();
+        assert_eq!(sizes, vec![2, 4]);
+    }
+
+    #[test]
+    fn grid_components() {
+        let grid = Grid::from_chars("##..\n#..#\n..##\n#...", |c| Ok(c == '#')).unwrap();
+        let mut sizes = component_sizes(&grid);
+        sizes.sort();
+        assert_eq!(sizes, vec![1, 3, 3]);
+    }
+}
@@ -0,0 +1,327 @@
+//! A dense rectangle of cells, the shape of most puzzle maps.
+//!
+//! Cells are addressed with a `geom::Point`, with the origin in the top left
+//! corner of the input, just as the characters were laid out.
+
+use std::fmt;
+use std::iter;
+use std::ops::{Index, IndexMut};
+
+use geom::Point;
+use Result;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// A grid with each cell computed from its position.
+    pub fn from_fn<F: FnMut(Point) -> T>(width: usize, height: usize, f: F) -> Grid<T> {
+        let mut grid = Grid {
+            width,
+            height,
+            cells: Vec::with_capacity(width * height),
+        };
+
+        grid.cells = grid.points().map(f).collect();
+        grid
+    }
+
+    /// A grid from its rows, which must all have the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+
+        for (y, row) in rows.into_iter().enumerate() {
+            ensure!(
+                row.len() == width,
+                "row {} has {} cells, expected {}",
+                y + 1,
+                row.len(),
+                width
+            );
+            cells.extend(row);
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a character map, with `f` turning each character into a cell.
+    pub fn from_chars<F>(s: &str, mut f: F) -> Result<Grid<T>>
+    where
+        F: FnMut(char) -> Result<T>,
+    {
+        let rows = s
+            .lines()
+            .map(|line| line.chars().map(&mut f).collect())
+            .collect::<Result<_>>()?;
+
+        Grid::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn offset(&self, p: Point) -> Option<usize> {
+        if p.x < 0 || p.y < 0 {
+            return None;
+        }
+
+        let (x, y) = (p.x as usize, p.y as usize);
+        if x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.offset(p).is_some()
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.offset(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        match self.offset(p) {
+            Some(i) => Some(&mut self.cells[i]),
+            None => None,
+        }
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} is outside the grid", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// A grid of the same shape, with `f` applied to every cell.
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Draws the grid with one character per cell, without a final newline.
+    pub fn render<F: FnMut(&T) -> char>(&self, mut f: F) -> String {
+        let rows: Vec<String> = self
+            .rows()
+            .map(|row| row.iter().map(&mut f).collect())
+            .collect();
+        rows.join("\n")
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// The `width` by `height` part of the grid starting at `origin`.
+    pub fn subgrid(&self, origin: Point, width: usize, height: usize) -> Grid<T> {
+        Grid::from_fn(width, height, |p| self[origin + p].clone())
+    }
+
+    /// Mirrors the grid along its main diagonal.
+    pub fn transpose(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |p| {
+            self[Point::new(p.y, p.x)].clone()
+        })
+    }
+
+    /// Rotates the grid a quarter turn clockwise.
+    pub fn rotate_right(&self) -> Grid<T> {
+        let h = self.height as i32;
+        Grid::from_fn(self.height, self.width, |p| {
+            self[Point::new(p.y, h - 1 - p.x)].clone()
+        })
+    }
+
+    /// Rotates the grid a quarter turn anticlockwise.
+    pub fn rotate_left(&self) -> Grid<T> {
+        let w = self.width as i32;
+        Grid::from_fn(self.height, self.width, |p| {
+            self[Point::new(w - 1 - p.y, p.x)].clone()
+        })
+    }
+
+    /// Swaps the left and right sides.
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        let w = self.width as i32;
+        Grid::from_fn(self.width, self.height, |p| {
+            self[Point::new(w - 1 - p.x, p.y)].clone()
+        })
+    }
+
+    /// Swaps the top and bottom.
+    pub fn flip_vertical(&self) -> Grid<T> {
+        let h = self.height as i32;
+        Grid::from_fn(self.width, self.height, |p| {
+            self[Point::new(p.x, h - 1 - p.y)].clone()
+        })
+    }
+
+    /// Adds `n` cells of `fill` on every side, so every existing cell moves
+    /// by `(n, n)`.
+    pub fn grow(&mut self, n: usize, fill: T) {
+        let width = self.width + 2 * n;
+        let height = self.height + 2 * n;
+        let mut cells = Vec::with_capacity(width * height);
+
+        cells.resize(n * width, fill.clone());
+        for row in self.rows() {
+            cells.extend(iter::repeat(fill.clone()).take(n));
+            cells.extend_from_slice(row);
+            cells.extend(iter::repeat(fill.clone()).take(n));
+        }
+        cells.resize(width * height, fill);
+
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        match self.offset(p) {
+            Some(i) => &self.cells[i],
+            None => panic!("{:?} is outside the {}x{} grid", p, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        match self.offset(p) {
+            Some(i) => &mut self.cells[i],
+            None => panic!("{:?} is outside the {}x{} grid", p, self.width, self.height),
+        }
+    }
+}
+
+/// One character per cell, the way the grid was parsed.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Grid<char> {
+        Grid::from_chars(s, Ok).unwrap()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let grid = chars("ab.\n#cd");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(2, 1)], 'd');
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid.to_string(), "ab.\n#cd");
+        assert_eq!(grid.render(|&c| if c == '.' { ' ' } else { c }), "ab \n#cd");
+
+        assert!(Grid::from_chars("ab\nc", Ok).is_err());
+        let digits = Grid::from_chars("1?", |c| -> Result<u32> {
+            c.to_digit(10)
+                .ok_or_else(|| format_err!("unexpected character: {}", c))
+        });
+        assert!(digits.is_err());
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = chars("abc\ndef");
+        let rows: Vec<String> = grid.rows().map(|r| r.iter().collect()).collect();
+        assert_eq!(rows, vec!["abc", "def"]);
+        let columns: Vec<String> = grid.columns().map(|c| c.collect()).collect();
+        assert_eq!(columns, vec!["ad", "be", "cf"]);
+        assert_eq!(grid.transpose(), chars("ad\nbe\ncf"));
+    }
+
+    #[test]
+    fn rotations_and_flips() {
+        let grid = chars("abc\ndef");
+        assert_eq!(grid.rotate_right(), chars("da\neb\nfc"));
+        assert_eq!(grid.rotate_left(), chars("cf\nbe\nad"));
+        assert_eq!(grid.flip_horizontal(), chars("cba\nfed"));
+        assert_eq!(grid.flip_vertical(), chars("def\nabc"));
+        assert_eq!(grid.rotate_right(), grid.transpose().flip_horizontal());
+        assert_eq!(grid.rotate_right().rotate_left(), grid);
+        assert_eq!(grid.subgrid(Point::new(1, 0), 2, 2), chars("bc\nef"));
+    }
+
+    #[test]
+    fn growth() {
+        let mut grid = chars("ab\ncd");
+        grid.grow(1, '.');
+        assert_eq!(grid.to_string(), "....\n.ab.\n.cd.\n....");
+        assert_eq!(grid[Point::new(2, 2)], 'd');
+
+        let mut empty = Grid::new(0, 0, '#');
+        empty.grow(2, '.');
+        assert_eq!(empty, Grid::new(4, 4, '.'));
+    }
+}
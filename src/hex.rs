@@ -0,0 +1,280 @@
+//! Hexagonal grids of flat-topped hexagons, in cube coordinates.
+//!
+//! A hex is stored by its axial coordinates `q` and `r`; the third cube
+//! coordinate `s` always makes `q + r + s == 0`. North is `r - 1`.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use failure::Error;
+
+use self::Direction::{N, NE, NW, S, SE, SW};
+use Result;
+
+/// The neighbours of a flat-topped hexagon, one across each side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    N,
+    NE,
+    SE,
+    S,
+    SW,
+    NW,
+}
+
+impl Direction {
+    /// All directions, in clockwise order.
+    pub const ALL: [Direction; 6] = [N, NE, SE, S, SW, NW];
+
+    pub fn reverse(self) -> Direction {
+        match self {
+            N => S,
+            NE => SW,
+            SE => NW,
+            S => N,
+            SW => NE,
+            NW => SE,
+        }
+    }
+
+    /// The change in `(q, r)` of a single step in this direction.
+    pub fn offset(self) -> Hex {
+        match self {
+            N => Hex::new(0, -1),
+            NE => Hex::new(1, -1),
+            SE => Hex::new(1, 0),
+            S => Hex::new(0, 1),
+            SW => Hex::new(-1, 1),
+            NW => Hex::new(-1, 0),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Direction> {
+        let direction = match s {
+            "n" => N,
+            "ne" => NE,
+            "se" => SE,
+            "s" => S,
+            "sw" => SW,
+            "nw" => NW,
+            other => bail!("unexpected direction: {}", other),
+        };
+
+        Ok(direction)
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            N => "n",
+            NE => "ne",
+            SE => "se",
+            S => "s",
+            SW => "sw",
+            NW => "nw",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    pub fn new(q: i32, r: i32) -> Hex {
+        Hex { q, r }
+    }
+
+    pub fn origin() -> Hex {
+        Hex::default()
+    }
+
+    /// The third cube coordinate.
+    pub fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// The fewest steps between the two hexes.
+    pub fn distance(self, other: Hex) -> u32 {
+        let d = self - other;
+        ((d.q.abs() + d.r.abs() + d.s().abs()) / 2) as u32
+    }
+
+    pub fn step(self, d: Direction) -> Hex {
+        self + d.offset()
+    }
+
+    /// The hexes sharing a side with this one, clockwise from North.
+    pub fn neighbours(self) -> impl Iterator<Item = Hex> {
+        Direction::ALL.iter().map(move |&d| self.step(d))
+    }
+
+    /// The direction of a neighbouring hex, if `other` is one.
+    pub fn direction_to(self, other: Hex) -> Option<Direction> {
+        Direction::ALL
+            .iter()
+            .cloned()
+            .find(|&d| self.step(d) == other)
+    }
+
+    /// The hexes exactly `radius` steps away, clockwise from the South West.
+    pub fn ring(self, radius: u32) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![self];
+        }
+
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        let mut hex = self + SW.offset() * radius as i32;
+        for &d in &Direction::ALL {
+            for _ in 0..radius {
+                ring.push(hex);
+                hex = hex.step(d);
+            }
+        }
+
+        ring
+    }
+
+    /// The hexes on the straight line from this one to `other`, both ends
+    /// included, each a neighbour of the one before.
+    pub fn line(self, other: Hex) -> Vec<Hex> {
+        let n = self.distance(other);
+        if n == 0 {
+            return vec![self];
+        }
+
+        // Nudging the ends keeps points exactly between two hexes from
+        // rounding differently along the line.
+        let lerp = |a: i32, b: i32, nudge: f64, t: f64| {
+            let (a, b) = (f64::from(a) + nudge, f64::from(b) + nudge);
+            a + (b - a) * t
+        };
+
+        (0..=n)
+            .map(|i| {
+                let t = f64::from(i) / f64::from(n);
+                round(
+                    lerp(self.q, other.q, 1e-6, t),
+                    lerp(self.r, other.r, 2e-6, t),
+                    lerp(self.s(), other.s(), -3e-6, t),
+                )
+            })
+            .collect()
+    }
+}
+
+/// The hex containing the fractional cube coordinates.
+fn round(q: f64, r: f64, s: f64) -> Hex {
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+
+    Hex::new(rq as i32, rr as i32)
+}
+
+impl From<Direction> for Hex {
+    fn from(d: Direction) -> Hex {
+        d.offset()
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, rhs: Hex) -> Hex {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, rhs: Hex) -> Hex {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Hex {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, k: i32) -> Hex {
+        Hex::new(self.q * k, self.r * k)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Hex) {
+        *self = *self + rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directions() {
+        for &d in &Direction::ALL {
+            assert_eq!(d.to_string().parse::<Direction>().unwrap(), d);
+            assert_eq!(Hex::origin().step(d).step(d.reverse()), Hex::origin());
+            assert_eq!(Hex::origin().direction_to(d.into()), Some(d));
+        }
+
+        assert!("up".parse::<Direction>().is_err());
+        assert_eq!(Hex::origin().direction_to(Hex::new(2, 0)), None);
+    }
+
+    #[test]
+    fn distance() {
+        let p = Hex::origin().step(NE).step(NE).step(S).step(S);
+        assert_eq!(p, Hex::new(2, 0));
+        assert_eq!(p.distance(Hex::origin()), 2);
+        assert_eq!((N.offset() * 5).distance(S.offset() * 2), 7);
+        assert!(p.neighbours().all(|n| n.distance(p) == 1));
+    }
+
+    #[test]
+    fn rings() {
+        let center = Hex::new(3, -1);
+        assert_eq!(center.ring(0), vec![center]);
+        for radius in 1..5 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert!(ring.iter().all(|h| h.distance(center) == radius));
+            assert!(ring.windows(2).all(|w| w[0].distance(w[1]) == 1));
+        }
+    }
+
+    #[test]
+    fn lines() {
+        let (a, b) = (Hex::new(-2, 1), Hex::new(3, -4));
+        let line = a.line(b);
+        assert_eq!(line.len(), a.distance(b) as usize + 1);
+        assert_eq!((line[0], line[line.len() - 1]), (a, b));
+        assert!(line.windows(2).all(|w| w[0].distance(w[1]) == 1));
+        assert_eq!(a.line(a), vec![a]);
+    }
+}
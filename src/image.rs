@@ -0,0 +1,288 @@
+//! Pictures of what the solvers compute, saved as PPM or PNG.
+//!
+//! Both formats are written by hand: PPM is trivial, and PNG only needs a
+//! zlib stream, which may consist of uncompressed blocks.
+
+use std::fs;
+use std::path::Path;
+
+use geom::Point;
+use grid::Grid;
+use params::Overrides;
+use {missing, Result};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GREY: Rgb = Rgb(128, 128, 128);
+    pub const RED: Rgb = Rgb(220, 40, 40);
+    pub const GREEN: Rgb = Rgb(40, 200, 40);
+    pub const BLUE: Rgb = Rgb(60, 90, 230);
+    pub const YELLOW: Rgb = Rgb(240, 210, 40);
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pixels: Grid<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Image {
+        Image {
+            pixels: Grid::new(width, height, background),
+        }
+    }
+
+    /// One pixel per cell, coloured by `palette`.
+    pub fn from_grid<T, F: FnMut(&T) -> Rgb>(grid: &Grid<T>, palette: F) -> Image {
+        Image {
+            pixels: grid.map(palette),
+        }
+    }
+
+    /// Just large enough for all the points, which are drawn in order so
+    /// later ones cover earlier ones at the same position.
+    pub fn from_points<I>(points: I, background: Rgb) -> Image
+    where
+        I: IntoIterator<Item = (Point, Rgb)>,
+    {
+        let points: Vec<(Point, Rgb)> = points.into_iter().collect();
+        let min_x = points.iter().map(|(p, _)| p.x).min().unwrap_or(0);
+        let max_x = points.iter().map(|(p, _)| p.x).max().unwrap_or(-1);
+        let min_y = points.iter().map(|(p, _)| p.y).min().unwrap_or(0);
+        let max_y = points.iter().map(|(p, _)| p.y).max().unwrap_or(-1);
+
+        let mut pixels = Grid::new(
+            (max_x - min_x + 1) as usize,
+            (max_y - min_y + 1) as usize,
+            background,
+        );
+        let origin = Point::new(min_x, min_y);
+        for (p, colour) in points {
+            pixels[p - origin] = colour;
+        }
+
+        Image { pixels }
+    }
+
+    pub fn width(&self) -> usize {
+        self.pixels.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.pixels.height()
+    }
+
+    pub fn pixels(&self) -> &Grid<Rgb> {
+        &self.pixels
+    }
+
+    pub fn get(&self, p: Point) -> Option<Rgb> {
+        self.pixels.get(p).cloned()
+    }
+
+    pub fn set(&mut self, p: Point, colour: Rgb) {
+        if let Some(pixel) = self.pixels.get_mut(p) {
+            *pixel = colour;
+        }
+    }
+
+    /// Blows every pixel up to a `factor` by `factor` square.
+    pub fn scale(&self, factor: usize) -> Image {
+        let f = factor as i32;
+        let pixels = Grid::from_fn(self.width() * factor, self.height() * factor, |p| {
+            self.pixels[Point::new(p.x / f, p.y / f)]
+        });
+
+        Image { pixels }
+    }
+
+    /// The raw bytes of every row, three per pixel.
+    fn rgb_rows<'a>(&'a self) -> impl Iterator<Item = Vec<u8>> + 'a {
+        self.pixels
+            .rows()
+            .map(|row| row.iter().flat_map(|&Rgb(r, g, b)| vec![r, g, b]).collect())
+    }
+
+    /// Binary PPM, which most image viewers can show.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut out = format!("P6\n{} {}\n255\n", self.width(), self.height()).into_bytes();
+        for row in self.rgb_rows() {
+            out.extend(row);
+        }
+
+        out
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity((3 * self.width() + 1) * self.height());
+        for row in self.rgb_rows() {
+            // No filter
+            raw.push(0);
+            raw.extend(row);
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend(&be32(self.width() as u32));
+        header.extend(&be32(self.height() as u32));
+        // 8 bits per channel, RGB, deflate, no filtering choices, no interlacing
+        header.extend(&[8, 2, 0, 0, 0]);
+
+        let mut out = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        chunk(&mut out, b"IHDR", &header);
+        chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+        chunk(&mut out, b"IEND", &[]);
+        out
+    }
+
+    /// Saves as PNG, or as PPM if the extension of `path` says so.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let bytes = match path.extension().and_then(|e| e.to_str()) {
+            Some("ppm") => self.to_ppm(),
+            Some("png") => self.to_png(),
+            _ => bail!("{} should end in .png or .ppm", path.display()),
+        };
+
+        fs::write(path, bytes).map_err(|e| format_err!("failed to write {}: {}", path.display(), e))
+    }
+}
+
+fn be32(n: u32) -> [u8; 4] {
+    [(n >> 24) as u8, (n >> 16) as u8, (n >> 8) as u8, n as u8]
+}
+
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend(&be32(data.len() as u32));
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(&be32(crc));
+}
+
+/// A zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32K window, no preset dictionary, fastest
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+
+    if blocks.peek().is_none() {
+        out.extend(&[1, 0, 0, 0xff, 0xff]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(last as u8);
+        out.extend(&[len as u8, (len >> 8) as u8, !len as u8, (!len >> 8) as u8]);
+        out.extend(block);
+    }
+
+    out.extend(&be32(adler32(data)));
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut table = [0u32; 256];
+    for (n, entry) in table.iter_mut().enumerate() {
+        let mut c = n as u32;
+        for _ in 0..8 {
+            c = if c & 1 == 1 {
+                0xedb8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+        }
+        *entry = c;
+    }
+
+    !data.iter().fold(!0, |c, &b| {
+        table[((c ^ u32::from(b)) & 0xff) as usize] ^ (c >> 8)
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), &x| {
+        let a = (a + u32::from(x)) % 65_521;
+        (a, (b + a) % 65_521)
+    });
+
+    (b << 16) | a
+}
+
+/// Draws a picture from the input of a day, with the same parameters as its
+/// solver.
+pub type Renderer = fn(&str, &Overrides) -> Result<Image>;
+
+pub fn renderer(edition: u32, day: u32) -> Option<Renderer> {
+    use {seventeen, sixteen};
+
+    let renderer: Renderer = match (edition, day) {
+        (2016, 1) => plain!(sixteen::day1::render),
+        (2017, 14) => plain!(seventeen::day14::render),
+        (2017, 19) => plain!(seventeen::day19::render),
+        (2017, 21) => tuned!(seventeen::day21::render),
+        (2017, 22) => tuned!(seventeen::day22::render),
+        _ => return None,
+    };
+
+    Some(renderer)
+}
+
+pub fn render(edition: u32, day: u32, input: &str, overrides: &Overrides) -> Result<Image> {
+    match renderer(edition, day) {
+        Some(render) => render(input, overrides),
+        None => Err(missing("renderer", edition, day)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn ppm() {
+        let mut image = Image::new(2, 1, Rgb::BLACK);
+        image.set(Point::new(1, 0), Rgb(1, 2, 3));
+        assert_eq!(image.to_ppm(), b"P6\n2 1\n255\n\0\0\0\x01\x02\x03".to_vec());
+    }
+
+    #[test]
+    fn png() {
+        let image = Image::new(300, 300, Rgb::WHITE);
+        let png = image.to_png();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+
+        // The pixel data needs more than one stored block
+        assert_eq!(&png[33..37], &be32(2 + 901 * 300 + 5 * 5 + 4));
+        assert_eq!(&png[37..41], b"IDAT");
+    }
+
+    #[test]
+    fn drawing() {
+        let grid = Grid::from_chars(".#\n#.", |c| Ok(c == '#')).unwrap();
+        let image = Image::from_grid(&grid, |&set| if set { Rgb::WHITE } else { Rgb::BLACK });
+        let scaled = image.scale(3);
+        assert_eq!((scaled.width(), scaled.height()), (6, 6));
+        assert_eq!(scaled.get(Point::new(4, 2)), Some(Rgb::WHITE));
+        assert_eq!(scaled.get(Point::new(5, 5)), Some(Rgb::BLACK));
+
+        let points = vec![(Point::new(-2, 3), Rgb::RED), (Point::new(1, 5), Rgb::BLUE)];
+        let image = Image::from_points(points, Rgb::BLACK);
+        assert_eq!((image.width(), image.height()), (4, 3));
+        assert_eq!(image.get(Point::new(0, 0)), Some(Rgb::RED));
+        assert_eq!(image.get(Point::new(3, 2)), Some(Rgb::BLUE));
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Deserializer};
+use serde_json;
+
+use Result;
+
+/// When a star was earned, in seconds since the epoch.
+pub type Timestamp = i64;
+
+/// A field that some exports give as a string and others as a number.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Raw {
+    Number(i64),
+    Text(String),
+}
+
+/// Older exports give timestamps as strings, newer ones as numbers.
+fn timestamp<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> ::std::result::Result<Timestamp, D::Error> {
+    use serde::de::Error;
+
+    match Raw::deserialize(deserializer)? {
+        Raw::Number(ts) => Ok(ts),
+        Raw::Text(s) => s.parse().map_err(D::Error::custom),
+    }
+}
+
+/// Older exports give ids as strings, newer ones as numbers.
+fn id<'de, D: Deserializer<'de>>(deserializer: D) -> ::std::result::Result<String, D::Error> {
+    match Raw::deserialize(deserializer)? {
+        Raw::Number(id) => Ok(id.to_string()),
+        Raw::Text(s) => Ok(s),
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Star {
+    #[serde(deserialize_with = "timestamp")]
+    pub get_star_ts: Timestamp,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Member {
+    #[serde(deserialize_with = "id")]
+    pub id: String,
+    /// Missing for anonymous users
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u32,
+    /// Stars by day and then by part, both as strings
+    #[serde(default)]
+    pub completion_day_level: BTreeMap<String, BTreeMap<String, Star>>,
+}
+
+impl Member {
+    pub fn display_name(&self) -> String {
+        match self.name {
+            Some(ref name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    fn star(&self, day: u32, part: u32) -> Option<Timestamp> {
+        self.completion_day_level
+            .get(&day.to_string())
+            .and_then(|parts| parts.get(&part.to_string()))
+            .map(|star| star.get_star_ts)
+    }
+
+    /// Seconds between earning the first and the second star of `day`.
+    pub fn delta(&self, day: u32) -> Option<i64> {
+        Some(self.star(day, 2)? - self.star(day, 1)?)
+    }
+
+    /// The days on which at least one star was earned.
+    pub fn days(&self) -> Vec<u32> {
+        let mut days: Vec<u32> = self
+            .completion_day_level
+            .keys()
+            .filter_map(|d| d.parse().ok())
+            .collect();
+        days.sort();
+        days
+    }
+}
+
+/// A private leaderboard, as exported from its page on adventofcode.com.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Leaderboard {
+    pub event: String,
+    #[serde(deserialize_with = "id")]
+    pub owner_id: String,
+    pub members: BTreeMap<String, Member>,
+}
+
+impl Leaderboard {
+    pub fn parse(s: &str) -> Result<Leaderboard> {
+        serde_json::from_str(s).map_err(Into::into)
+    }
+
+    pub fn load(path: &Path) -> Result<Leaderboard> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format_err!("failed to read {}: {}", path.display(), e))?;
+
+        Leaderboard::parse(&contents).map_err(|e| format_err!("invalid {}: {}", path.display(), e))
+    }
+
+    /// Members by descending local score, then by name.
+    pub fn ranking(&self) -> Vec<&Member> {
+        let mut members: Vec<&Member> = self.members.values().collect();
+        members.sort_by(|a, b| {
+            b.local_score
+                .cmp(&a.local_score)
+                .then_with(|| a.display_name().cmp(&b.display_name()))
+        });
+        members
+    }
+}
+
+fn format_delta(secs: i64) -> String {
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+impl fmt::Display for Leaderboard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ranking = self.ranking();
+        let width = ranking
+            .iter()
+            .map(|m| m.display_name().len())
+            .max()
+            .unwrap_or(0)
+            .max(6);
+
+        writeln!(f, "Leaderboard {}", self.event)?;
+        writeln!(f, "{:<w$}  stars  score", "member", w = width)?;
+        for member in &ranking {
+            writeln!(
+                f,
+                "{:<w$}  {:>5}  {:>5}",
+                member.display_name(),
+                member.stars,
+                member.local_score,
+                w = width
+            )?;
+        }
+
+        for member in ranking.iter().filter(|m| !m.days().is_empty()) {
+            writeln!(f, "\n{}", member.display_name())?;
+            for day in member.days() {
+                match member.delta(day) {
+                    Some(delta) => writeln!(f, "  day {:>2}  {:>10}", day, format_delta(delta))?,
+                    None => writeln!(f, "  day {:>2}  {:>10}", day, "part 1")?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "event": "2017",
+        "owner_id": "1",
+        "members": {
+            "1": {
+                "id": "1", "name": "alice", "stars": 3, "local_score": 10,
+                "global_score": 0, "last_star_ts": "1512190000",
+                "completion_day_level": {
+                    "1": {"1": {"get_star_ts": "1512104500"}, "2": {"get_star_ts": "1512104692"}},
+                    "2": {"1": {"get_star_ts": 1512190000}}
+                }
+            },
+            "2": {
+                "id": "2", "name": null, "stars": 0, "local_score": 0,
+                "global_score": 0, "last_star_ts": 0, "completion_day_level": {}
+            }
+        }
+    }"#;
+
+    #[test]
+    fn parse_export() {
+        let board = Leaderboard::parse(EXPORT).unwrap();
+        let alice = &board.members["1"];
+        assert_eq!(alice.days(), vec![1, 2]);
+        assert_eq!(alice.delta(1), Some(192));
+        assert_eq!(alice.delta(2), None);
+        assert_eq!(board.members["2"].display_name(), "(anonymous user #2)");
+    }
+
+    #[test]
+    fn numeric_ids() {
+        let board = Leaderboard::parse(
+            r#"{
+                "event": "2023",
+                "owner_id": 12345,
+                "members": {
+                    "12345": {
+                        "id": 12345, "name": null, "stars": 2, "local_score": 4,
+                        "global_score": 0, "last_star_ts": 1701407000,
+                        "completion_day_level": {
+                            "1": {
+                                "1": {"get_star_ts": 1701406800, "star_index": 1},
+                                "2": {"get_star_ts": 1701407000, "star_index": 2}
+                            }
+                        }
+                    }
+                }
+            }"#,
+        ).unwrap();
+        assert_eq!(board.owner_id, "12345");
+        let member = &board.members["12345"];
+        assert_eq!(member.id, "12345");
+        assert_eq!(member.display_name(), "(anonymous user #12345)");
+        assert_eq!(member.delta(1), Some(200));
+    }
+
+    #[test]
+    fn display() {
+        let board = Leaderboard::parse(EXPORT).unwrap();
+        assert_eq!(
+            board.to_string(),
+            "Leaderboard 2017\n\
+             member               stars  score\n\
+             alice                    3     10\n\
+             (anonymous user #2)      0      0\n\
+             \n\
+             alice\n  \
+             day  1     0:03:12\n  \
+             day  2      part 1\n"
+        );
+    }
+}
@@ -24,21 +24,202 @@ extern crate log;
 #[macro_use]
 extern crate nom;
 extern crate parking_lot;
+extern crate rand;
 extern crate rayon;
 extern crate regex;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
 extern crate test;
+extern crate termion;
+extern crate toml;
+
+/// Wraps a function of a day without parameters, such as its `solve`, into
+/// one that also takes the overrides and rejects any it is given. Arguments
+/// named after the function, like a `recorder`, are passed on after the input.
+macro_rules! plain {
+    ($f:path $(, $arg:ident)*) => {
+        |input: &str, overrides: &Overrides $(, $arg)*| {
+            overrides.ensure_empty()?;
+            $f(input $(, $arg)*)
+        }
+    };
+}
 
+/// Wraps a function of a day that takes the same `Params` as its `solve`,
+/// building them from the overrides.
+macro_rules! tuned {
+    ($f:path $(, $arg:ident)*) => {
+        |input: &str, overrides: &Overrides $(, $arg)*| {
+            let params = overrides.apply()?;
+            $f(input, &params $(, $arg)*)
+        }
+    };
+}
+
+pub mod animation;
+pub mod answer;
+pub mod automaton;
+pub mod batch;
+pub mod bitgrid;
+pub mod catalogue;
+pub mod config;
+pub mod cycle;
+pub mod disasm;
+pub mod generate;
+pub mod geom;
+pub mod graph;
+pub mod grid;
+pub mod hex;
+pub mod image;
+pub mod leaderboard;
+pub mod numtheory;
+pub mod ocr;
+pub mod params;
+pub mod parsers;
+pub mod repeat;
+pub mod search;
 pub mod seventeen;
 pub mod sixteen;
+pub mod tui;
+pub mod variants;
+pub mod vm;
 
-use std::fmt::{Debug, Display};
+use std::fmt::{self, Debug};
 use std::io::{self, Read};
 use std::result;
 
 use failure::Error;
+use rand::prng::XorShiftRng;
+use rand::SeedableRng;
+
+pub use answer::Answer;
+use params::Overrides;
 
 pub type Result<T> = result::Result<T, Error>;
 
+/// A solver takes the raw puzzle input and produces the answers to both parts.
+pub type Solver = fn(&str, &Overrides) -> Result<Answers>;
+
+/// The answers to a single puzzle. Some days only have one part implemented.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Answers {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+}
+
+impl Answers {
+    pub fn new<S: Into<Answer>, T: Into<Answer>>(part1: T, part2: S) -> Answers {
+        Answers {
+            part1: Some(part1.into()),
+            part2: Some(part2.into()),
+        }
+    }
+
+    pub fn part1<T: Into<Answer>>(part1: T) -> Answers {
+        Answers {
+            part1: Some(part1.into()),
+            part2: None,
+        }
+    }
+
+    pub fn part2<T: Into<Answer>>(part2: T) -> Answers {
+        Answers {
+            part1: None,
+            part2: Some(part2.into()),
+        }
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (n, part) in [&self.part1, &self.part2].iter().enumerate() {
+            match part {
+                Some(answer) if answer.is_multiline() => {
+                    writeln!(f, "Part {}:\n{}", n + 1, answer)?
+                }
+                Some(answer) => writeln!(f, "Part {}: {}", n + 1, answer)?,
+                None => (),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn solver(edition: u32, day: u32) -> Result<Solver> {
+    let solver: Solver = match edition {
+        2016 => {
+            use sixteen::*;
+            match day {
+                1 => plain!(day1::solve),
+                2 => plain!(day2::solve),
+                3 => plain!(day3::solve),
+                4 => plain!(day4::solve),
+                5 => plain!(day5::solve),
+                6 => plain!(day6::solve),
+                7 => plain!(day7::solve),
+                _ => return Err(unavailable(edition, day)),
+            }
+        }
+        2017 => {
+            use seventeen::*;
+            match day {
+                1 => plain!(day1::solve),
+                2 => plain!(day2::solve),
+                3 => tuned!(day3::solve),
+                4 => plain!(day4::solve),
+                5 => plain!(day5::solve),
+                6 => plain!(day6::solve),
+                7 => plain!(day7::solve),
+                8 => plain!(day8::solve),
+                9 => plain!(day9::solve),
+                10 => plain!(day10::solve),
+                11 => plain!(day11::solve),
+                12 => plain!(day12::solve),
+                13 => plain!(day13::solve),
+                14 => plain!(day14::solve),
+                15 => tuned!(day15::solve),
+                16 => plain!(day16::solve),
+                17 => tuned!(day17::solve),
+                18 => plain!(day18::solve),
+                19 => plain!(day19::solve),
+                20 => plain!(day20::solve),
+                21 => tuned!(day21::solve),
+                22 => tuned!(day22::solve),
+                23 => plain!(day23::solve),
+                24 => plain!(day24::solve),
+                25 => plain!(day25::solve),
+                _ => return Err(unavailable(edition, day)),
+            }
+        }
+        _ => return Err(unavailable(edition, day)),
+    };
+
+    Ok(solver)
+}
+
+/// Explains why there is no solver, telling days that are missing apart
+/// from ones that don't exist.
+fn unavailable(edition: u32, day: u32) -> Error {
+    if let Some(puzzle) = catalogue::find(edition, day) {
+        format_err!("{} is not implemented yet", puzzle)
+    } else if catalogue::list(Some(edition)).is_empty() {
+        format_err!("<year> must be an integer in the range (2016...2017)")
+    } else {
+        format_err!("<day> must be an integer in the range (1...25)")
+    }
+}
+
+/// Explains why a day has no `feature`, such as a renderer or a generator.
+pub fn missing(feature: &str, edition: u32, day: u32) -> Error {
+    match catalogue::find(edition, day) {
+        Some(puzzle) => format_err!("there is no {} for {}", feature, puzzle),
+        None => format_err!("there is no puzzle {} day {}", edition, day),
+    }
+}
+
 #[allow(dead_code)]
 pub fn check<T>(result: Result<T>, expected: T)
 where
@@ -56,6 +237,18 @@ where
     }
 }
 
+/// A deterministic random number generator, for reproducible generated inputs.
+pub fn seeded_rng(seed: u64) -> XorShiftRng {
+    let mut bytes = [0; 16];
+    for (i, b) in bytes.iter_mut().enumerate() {
+        // the upper half is inverted, so the seed is never all zeroes
+        let word = if i < 8 { seed } else { !seed };
+        *b = (word >> (8 * (i % 8))) as u8;
+    }
+
+    XorShiftRng::from_seed(bytes)
+}
+
 pub fn get_input() -> Result<String> {
     let mut buffer = String::new();
     io::stdin().read_to_string(&mut buffer)?;
@@ -63,7 +256,6 @@ pub fn get_input() -> Result<String> {
     Ok(buffer)
 }
 
-pub fn print_output<S: Display, T: Display>(day: u8, part1: T, part2: S) -> Result<()> {
-    println!("Day {}:\nPart 1: {}\nPart 2: {}", day, part1, part2);
-    Ok(())
+pub fn print_output(day: u32, answers: &Answers) {
+    println!("Day {}:\n{}", day, answers);
 }
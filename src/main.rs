@@ -5,67 +5,315 @@ extern crate failure;
 extern crate log;
 #[macro_use]
 extern crate quicli;
+#[macro_use]
+extern crate serde_json;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use adventofcode::{seventeen, sixteen};
+use adventofcode::animation::{self, Recorder, Viewport};
+use adventofcode::catalogue::{self, Table};
+use adventofcode::config::{Config, Format};
+use adventofcode::disasm;
+use adventofcode::generate;
+use adventofcode::image;
+use adventofcode::leaderboard::Leaderboard;
+use adventofcode::repeat;
+use adventofcode::tui;
+use adventofcode::params::{self, Overrides};
+use adventofcode::{batch, get_input, print_output, solver, variants};
 use quicli::prelude::*;
 
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Show stars, scores and part 2 times from an exported private leaderboard
+    #[structopt(name = "leaderboard")]
+    Leaderboard {
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Print a random input for a day, for stress testing its solver
+    #[structopt(name = "generate")]
+    Generate {
+        edition: u32,
+        day: u32,
+        /// How big the input should be, in units that depend on the day
+        #[structopt(long = "size", default_value = "100")]
+        size: usize,
+        #[structopt(long = "seed", default_value = "0")]
+        seed: u64,
+    },
+    /// List the puzzles, showing which are solved, tested and benchmarked
+    #[structopt(name = "list")]
+    List {
+        /// Only list the puzzles of this edition
+        edition: Option<u32>,
+    },
+}
+
 #[derive(Debug, StructOpt)]
 struct Cli {
-    /// The edition of adventofcode the problem belongs to
-    edition: u32,
+    #[structopt(subcommand)]
+    cmd: Option<Command>,
+    /// The edition of adventofcode the problem belongs to. If only one number
+    /// is given, it is the day and the edition comes from the config file
+    edition: Option<u32>,
     /// The day of the event the problem corresponds to
-    day: u32,
+    day: Option<u32>,
+    /// Read defaults from this file instead of `aoc.toml`
+    #[structopt(long = "config", parse(from_os_str))]
+    config: Option<PathBuf>,
+    /// Read the input from `<data-dir>/<edition>/<day>` when that file exists
+    #[structopt(long = "data-dir", parse(from_os_str))]
+    data_dir: Option<PathBuf>,
+    /// Solve every input file in this directory instead of reading stdin
+    #[structopt(long = "inputs", parse(from_os_str))]
+    inputs: Option<PathBuf>,
+    /// Output format, either text or json
+    #[structopt(long = "format")]
+    format: Option<Format>,
+    /// Give up on a solver after this many seconds
+    #[structopt(long = "timeout")]
+    timeout: Option<u64>,
+    /// Override a puzzle parameter, e.g. `-p part1_bursts=70`
+    #[structopt(
+        short = "p",
+        long = "param",
+        raw(number_of_values = "1"),
+        parse(try_from_str = "params::parse_pair")
+    )]
+    params: Vec<(String, String)>,
+    /// Solve the day this many times on thread pools of different sizes, and
+    /// check that the answers are always the same
+    #[structopt(long = "repeat")]
+    repeat: Option<usize>,
+    /// Check that the alternative implementations of the day agree, instead
+    /// of solving it
+    #[structopt(long = "variants")]
+    variants: bool,
+    /// Number of random inputs generated by `--variants`
+    #[structopt(long = "cases", default_value = "100")]
+    cases: usize,
+    /// Seed for the inputs generated by `--variants`
+    #[structopt(long = "seed", default_value = "0")]
+    seed: u64,
+    /// Also draw what the solver works on, as a PNG or PPM image depending
+    /// on the extension
+    #[structopt(long = "render", parse(from_os_str))]
+    render: Option<PathBuf>,
+    /// Also record the steps of the simulation to this animated GIF
+    #[structopt(long = "animate", parse(from_os_str))]
+    animate: Option<PathBuf>,
+    /// Only keep every this many steps as frames of `--animate`
+    #[structopt(long = "every", default_value = "1")]
+    every: usize,
+    /// Size in pixels of every cell in `--animate`
+    #[structopt(long = "scale", default_value = "4")]
+    scale: usize,
+    /// Only show this part of the simulation in `--animate`, as
+    /// `x,y,width,height`
+    #[structopt(long = "viewport", allow_hyphen_values = true)]
+    viewport: Option<Viewport>,
+    /// Watch the simulation step by step in the terminal instead of solving
+    /// the day
+    #[structopt(long = "watch")]
+    watch: bool,
+    /// Print the program that is the input as a listing with labelled jumps
+    /// and basic blocks instead of solving the day
+    #[structopt(long = "disassemble")]
+    disassemble: bool,
     #[structopt(flatten)]
     verbosity: Verbosity,
 }
 
+impl Cli {
+    fn puzzle(&self, config: &Config) -> Result<(u32, u32)> {
+        match (self.edition, self.day, config.edition) {
+            (Some(edition), Some(day), _) => Ok((edition, day)),
+            (Some(day), None, Some(edition)) => Ok((edition, day)),
+            (Some(_), None, None) => {
+                bail!("<edition> is required when no default edition is configured")
+            }
+            (None, _, _) => bail!("no puzzle given, expected <edition> <day>"),
+        }
+    }
+
+    /// Parameters from the config file, with those on the command line on top.
+    fn overrides(&self, config: &Config, edition: u32, day: u32) -> Overrides {
+        let mut overrides = config
+            .params(edition, day)
+            .map(Overrides::from_table)
+            .unwrap_or_default();
+
+        for (k, v) in &self.params {
+            overrides.insert(k.as_str(), v.as_str());
+        }
+
+        overrides
+    }
+}
+
+fn read_input(data_dir: Option<&Path>, edition: u32, day: u32) -> Result<String> {
+    if let Some(dir) = data_dir {
+        let path = dir.join(edition.to_string()).join(day.to_string());
+        if path.is_file() {
+            info!("reading input from {}", path.display());
+            return Ok(fs::read_to_string(path)?);
+        }
+    }
+
+    get_input()
+}
+
 main!(|args: Cli, log_level: verbosity| {
     debug!("{:?}", args);
-    let _ = match args.edition {
-        2016 => {
-            use sixteen::*;
-            match args.day {
-                1 => day1::solve(),
-                2 => day2::solve(),
-                3 => day3::solve(),
-                4 => day4::solve(),
-                5 => day5::solve(),
-                6 => day6::solve(),
-                7 => day7::solve(),
-                _ => bail!("<day> must be an integer in the range (1...25)"),
+    let config = Config::load(args.config.as_ref().map(PathBuf::as_path))?;
+    let format = args.format.or(config.format).unwrap_or_default();
+    match args.cmd {
+        Some(Command::Leaderboard { ref file }) => {
+            print!("{}", Leaderboard::load(file)?);
+            return Ok(());
+        }
+        Some(Command::Generate {
+            edition,
+            day,
+            size,
+            seed,
+        }) => {
+            println!("{}", generate::generate(edition, day, size, seed)?);
+            return Ok(());
+        }
+        Some(Command::List { edition }) => {
+            let puzzles = catalogue::list(edition.or(config.edition));
+            match format {
+                Format::Text => print!("{}", Table(&puzzles)),
+                Format::Json => {
+                    let puzzles: Vec<_> = puzzles
+                        .iter()
+                        .map(|p| {
+                            json!({
+                                "edition": p.edition,
+                                "day": p.day,
+                                "title": p.title,
+                                "implemented": p.implemented(),
+                                "tested": p.tested,
+                                "benchmarked": p.benchmarked,
+                            })
+                        })
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&puzzles)?);
+                }
             }
+            return Ok(());
         }
-        2017 => {
-            use seventeen::*;
-            match args.day {
-                1 => day1::solve(),
-                2 => day2::solve(),
-                3 => day3::solve(),
-                4 => day4::solve(),
-                5 => day5::solve(),
-                6 => day6::solve(),
-                7 => day7::solve(),
-                8 => day8::solve(),
-                9 => day9::solve(),
-                10 => day10::solve(),
-                11 => day11::solve(),
-                12 => day12::solve(),
-                13 => day13::solve(),
-                14 => day14::solve(),
-                15 => day15::solve(),
-                16 => day16::solve(),
-                17 => day17::solve(),
-                18 => day18::solve(),
-                19 => day19::solve(),
-                20 => day20::solve(),
-                21 => day21::solve(),
-                22 => day22::solve(),
-                23 => day23::solve(),
-                24 => day24::solve(),
-                25 => day25::solve(),
-                _ => bail!("<day> must be an integer in the range (1...25)"),
+        None => (),
+    }
+
+    let (edition, day) = args.puzzle(&config)?;
+    let timeout = args.timeout.or(config.timeout).map(Duration::from_secs);
+    let data_dir = args.data_dir.as_ref().or(config.data_dir.as_ref());
+    let overrides = args.overrides(&config, edition, day);
+    let solve = solver(edition, day)?;
+
+    if args.variants {
+        let suite = variants::suite(edition, day).ok_or_else(|| {
+            format_err!("no alternative implementations for {} day {}", edition, day)
+        })?;
+        let report = variants::compare(&suite, args.cases, args.seed);
+        match format {
+            Format::Text => print!("{}", report),
+            Format::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        }
+
+        ensure!(
+            report.divergences.is_empty(),
+            "{} of {} comparisons diverged",
+            report.divergences.len(),
+            report.checked
+        );
+        return Ok(());
+    }
+
+    match args.inputs {
+        Some(_)
+            if args.render.is_some()
+                || args.animate.is_some()
+                || args.watch
+                || args.disassemble =>
+        {
+            bail!(
+                "--render, --animate, --watch and --disassemble need a single input, not --inputs"
+            )
+        }
+        Some(ref dir) => {
+            let report = batch::run(solve, dir, &overrides, timeout)?;
+            match format {
+                Format::Text => print!("{}", report),
+                Format::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+            }
+
+            let failures = report.failures();
+            if failures > 0 {
+                bail!("{} of {} inputs failed", failures, report.len());
+            }
+        }
+        None => {
+            let input = read_input(data_dir.map(PathBuf::as_path), edition, day)?;
+            if args.watch {
+                return tui::watch(edition, day, &input, &overrides);
+            }
+
+            if args.disassemble {
+                print!("{}", disasm::disassemble(edition, day, &input)?);
+                return Ok(());
+            }
+
+            if let Some(ref path) = args.render {
+                image::render(edition, day, &input, &overrides)?.save(path)?;
+                info!("saved a picture to {}", path.display());
+            }
+
+            if let Some(ref path) = args.animate {
+                let mut recorder = Recorder::new(args.every).scale(args.scale);
+                if let Some(viewport) = args.viewport {
+                    recorder = recorder.viewport(viewport);
+                }
+
+                animation::animate(edition, day, &input, &overrides, &mut recorder)?;
+                recorder.save(path)?;
+                info!("saved {} frames to {}", recorder.len(), path.display());
+            }
+
+            if let Some(times) = args.repeat {
+                let report = repeat::repeat(solve, &input, &overrides, times, timeout);
+                match format {
+                    Format::Text => print!("{}", report),
+                    Format::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+                }
+
+                ensure!(
+                    report.is_deterministic(),
+                    "{} runs gave {} different outcomes",
+                    report.runs.len(),
+                    report.distinct().len()
+                );
+                return Ok(());
+            }
+
+            let answers = batch::solve_timed(solve, &input, &overrides, timeout).into_result()?;
+            match format {
+                Format::Text => print_output(day, &answers),
+                Format::Json => {
+                    let output = json!({
+                        "edition": edition,
+                        "day": day,
+                        "part1": answers.part1,
+                        "part2": answers.part2,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                }
             }
         }
-        _ => bail!("<year> must be an integer in the range (2016...2017)"),
-    };
+    }
 });
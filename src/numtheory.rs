@@ -0,0 +1,230 @@
+//! Modular arithmetic, and solving systems of congruences.
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use fnv::FnvHashSet;
+
+/// Residue classes with more candidates than this are not worth listing up
+/// front, and are checked one candidate at a time instead.
+const WHEEL_LIMIT: usize = 1 << 16;
+
+/// `a mod m`, always between `0` and `m - 1`.
+pub fn modulo(a: i64, m: i64) -> i64 {
+    ((a % m) + m) % m
+}
+
+/// `a * b mod m`, without overflowing.
+pub fn mulmod(a: u64, b: u64, m: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(m)) as u64
+}
+
+/// `base^exp mod m`, by repeated squaring.
+pub fn modpow(base: u64, exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    let mut exp = exp;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mulmod(result, base, m);
+        }
+
+        base = mulmod(base, base, m);
+        exp >>= 1;
+    }
+
+    result
+}
+
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        a / gcd(a, b) * b
+    }
+}
+
+/// `(g, x, y)` such that `g` is the gcd of `a` and `b`, and `a*x + b*y == g`.
+pub fn egcd(a: i64, b: i64) -> (i64, i64, i64) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = egcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+/// The `x` with `a*x mod m == 1`, if `a` and `m` are coprime.
+pub fn modinv(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = egcd(modulo(a, m), m);
+    if g == 1 {
+        Some(modulo(x, m))
+    } else {
+        None
+    }
+}
+
+/// Solves `x ≡ a (mod m)` for every `(a, m)`, where the moduli need not be
+/// coprime. The solutions are all `x + k*l` for the returned `(x, l)`, with
+/// `0 <= x < l`; there are none if the congruences contradict each other,
+/// and nothing is returned either if `l` does not fit in an `i64`.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut x: i128 = 0;
+    let mut l: i128 = 1;
+
+    for &(a, m) in congruences {
+        let (a, m) = (i128::from(modulo(a, m)), i128::from(m));
+        let (g, p, _) = egcd(i64::try_from(l).ok()?, m as i64);
+        let g = i128::from(g);
+        if (a - x) % g != 0 {
+            return None;
+        }
+
+        let step = m / g;
+        let t = ((a - x) / g % step * i128::from(p) % step + step) % step;
+        x += l * t;
+        l *= step;
+        x = (x % l + l) % l;
+    }
+
+    Some((i64::try_from(x).ok()?, i64::try_from(l).ok()?))
+}
+
+/// The smallest `x >= 0` with `x ≡ a (mod m)` for every congruence and
+/// `x ≢ b (mod n)` for every `(b, n)` exclusion, if there is one.
+pub fn crt_excluding(congruences: &[(i64, i64)], exclusions: &[(i64, i64)]) -> Option<i64> {
+    let (x, l) = crt(congruences)?;
+
+    // Writing the solutions as `x + k*l` turns every exclusion into one on
+    // `k`, or drops it if no solution can ever hit it.
+    let mut on_k: BTreeMap<i64, FnvHashSet<i64>> = BTreeMap::new();
+    for &(b, n) in exclusions {
+        let (g, _, _) = egcd(l, n);
+        let d = modulo(b - x, n);
+        if d % g != 0 {
+            continue;
+        }
+
+        let n = n / g;
+        let k = match modinv(l / g, n) {
+            Some(inv) => modulo((d / g) % n * inv, n),
+            None => 0,
+        };
+        on_k.entry(n).or_insert_with(FnvHashSet::default).insert(k);
+    }
+
+    first_allowed(on_k).map(|k| x + k * l)
+}
+
+/// The smallest `k >= 0` not in any of the excluded residue classes, kept by
+/// modulus.
+fn first_allowed(exclusions: BTreeMap<i64, FnvHashSet<i64>>) -> Option<i64> {
+    let mut wheel = vec![0];
+    let mut size = 1;
+    let mut rest = Vec::new();
+
+    // Lists the residues modulo the lcm of the smallest moduli that survive
+    // their exclusions, as long as there aren't too many of them.
+    for (m, residues) in exclusions {
+        if residues.len() as i64 >= m {
+            return None;
+        }
+
+        let l = lcm(size as u64, m as u64) as i64;
+        if wheel.len() * (l / size) as usize > WHEEL_LIMIT {
+            rest.push((m, residues));
+            continue;
+        }
+
+        let next: Vec<i64> = (0..l / size)
+            .flat_map(|turn| wheel.iter().map(move |&r| r + turn * size))
+            .filter(|r| !residues.contains(&(r % m)))
+            .collect();
+        wheel = next;
+        size = l;
+
+        if wheel.is_empty() {
+            return None;
+        }
+    }
+
+    wheel.sort();
+
+    // Beyond the lcm of all moduli the same pattern repeats.
+    let period = rest.iter().fold(Some(size), |p, &(m, _)| {
+        p.and_then(|p| (p / gcd(p as u64, m as u64) as i64).checked_mul(m))
+    });
+    let turns = period.map_or(i64::max_value() / size, |p| p / size);
+
+    for turn in 0..turns {
+        for &r in &wheel {
+            let k = turn * size + r;
+            if rest
+                .iter()
+                .all(|(m, residues)| !residues.contains(&(k % m)))
+            {
+                return Some(k);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(modpow(2, 10, 1000), 24);
+        assert_eq!(modpow(16_807, 0, 7), 1);
+        assert_eq!(modpow(3, u64::max_value(), 1), 0);
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(modulo(-7, 3), 2);
+
+        let (g, x, y) = egcd(240, 46);
+        assert_eq!((g, 240 * x + 46 * y), (2, 2));
+        assert_eq!(modinv(3, 11), Some(4));
+        assert_eq!(modinv(6, 9), None);
+    }
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+
+        // Three primes around a million still fit in an i64, four don't
+        let big = [
+            (1, 1_000_003),
+            (1, 1_000_033),
+            (1, 1_000_037),
+            (1, 1_000_039),
+        ];
+        assert_eq!(crt(&big[..3]).map(|(x, _)| x), Some(1));
+        assert_eq!(crt(&big), None);
+    }
+
+    #[test]
+    fn exclusions() {
+        assert_eq!(crt_excluding(&[(2, 3)], &[(2, 6), (5, 9)]), Some(11));
+        assert_eq!(crt_excluding(&[], &[(0, 2), (1, 2)]), None);
+        assert_eq!(crt_excluding(&[(1, 2)], &[(0, 4)]), Some(1));
+
+        let exclusions = [(0, 4), (1, 2), (2, 6), (0, 6)];
+        let brute = (0..).find(|x| exclusions.iter().all(|&(b, n)| x % n != b));
+        assert_eq!(crt_excluding(&[], &exclusions), brute);
+    }
+}
@@ -0,0 +1,253 @@
+//! Reading answers drawn as block letters, such as
+//!
+//! ```text
+//! #..#.####.#....#.....##.
+//! #..#.#....#....#....#..#
+//! ####.###..#....#....#..#
+//! #..#.#....#....#....#..#
+//! #..#.#....#....#....#..#
+//! #..#.####.####.####..##.
+//! ```
+//!
+//! Letters are 6 rows tall and about 4 columns wide, or 10 rows tall and 6
+//! wide, and are separated by at least one empty column.
+
+use fnv::FnvHashMap;
+
+use geom::Point;
+use grid::Grid;
+use Result;
+
+/// The letters 6 rows tall, as far as they are known.
+const SMALL: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// The letters 10 rows tall, as far as they are known.
+const LARGE: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+lazy_static! {
+    static ref LETTERS: FnvHashMap<Grid<bool>, char> = SMALL
+        .iter()
+        .chain(LARGE)
+        .map(|&(letter, art)| (parse(art).expect("malformed letter"), letter))
+        .collect();
+}
+
+/// `#` and `█` are set, `.` and spaces aren't.
+fn parse(s: &str) -> Result<Grid<bool>> {
+    Grid::from_chars(s.trim_matches('\n'), |c| match c {
+        '#' | '█' => Ok(true),
+        '.' | ' ' => Ok(false),
+        other => Err(format_err!("unexpected character in letters: {}", other)),
+    })
+}
+
+/// Reads the letters drawn with `#` and `.`.
+pub fn read(s: &str) -> Result<String> {
+    read_grid(&parse(s)?)
+}
+
+/// Reads the letters drawn by the set cells.
+pub fn read_grid(grid: &Grid<bool>) -> Result<String> {
+    let glyphs = split(grid);
+    ensure!(!glyphs.is_empty(), "there are no letters to read");
+
+    let height = glyphs[0].height();
+    ensure!(
+        height == 6 || height == 10,
+        "letters are 6 or 10 rows tall, not {}",
+        height
+    );
+
+    let letters: Vec<Option<char>> = glyphs
+        .iter()
+        .map(|glyph| LETTERS.get(glyph).cloned())
+        .collect();
+
+    if letters.iter().all(Option::is_some) {
+        return Ok(letters.into_iter().flat_map(|l| l).collect());
+    }
+
+    let partial: String = letters.iter().map(|l| l.unwrap_or('?')).collect();
+    let unknown: Vec<String> = letters
+        .iter()
+        .zip(&glyphs)
+        .enumerate()
+        .filter(|&(_, (letter, _))| letter.is_none())
+        .map(|(i, (_, glyph))| {
+            let art = glyph.render(|&set| if set { '#' } else { '.' });
+            format!("letter {}:\n{}", i + 1, art)
+        })
+        .collect();
+
+    bail!(
+        "could not read {} of the letters in {}\n\n{}",
+        unknown.len(),
+        partial,
+        unknown.join("\n\n")
+    )
+}
+
+/// The letters, each trimmed down to the columns it uses, between the first
+/// and the last rows with anything set.
+fn split(grid: &Grid<bool>) -> Vec<Grid<bool>> {
+    let filled: Vec<usize> = grid
+        .rows()
+        .enumerate()
+        .filter(|(_, row)| row.iter().any(|&set| set))
+        .map(|(y, _)| y)
+        .collect();
+    let (top, bottom) = match (filled.first(), filled.last()) {
+        (Some(&top), Some(&bottom)) => (top, bottom),
+        _ => return Vec::new(),
+    };
+
+    let used: Vec<bool> = grid
+        .columns()
+        .map(|mut column| column.any(|&set| set))
+        .collect();
+
+    let mut glyphs = Vec::new();
+    let mut x = 0;
+    while x < used.len() {
+        if !used[x] {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < used.len() && used[x] {
+            x += 1;
+        }
+
+        let origin = Point::new(start as i32, top as i32);
+        glyphs.push(grid.subgrid(origin, x - start, bottom - top + 1));
+    }
+
+    glyphs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fonts() {
+        for &(letter, art) in SMALL.iter().chain(LARGE) {
+            assert_eq!(read(art).unwrap(), letter.to_string());
+        }
+    }
+
+    #[test]
+    fn words() {
+        let small = "
+#..#.####.#....#.....##.
+#..#.#....#....#....#..#
+####.###..#....#....#..#
+#..#.#....#....#....#..#
+#..#.#....#....#....#..#
+#..#.####.####.####..##.
+";
+        assert_eq!(read(small).unwrap(), "HELLO");
+
+        let large = [LARGE[0].1, LARGE[13].1, LARGE[14].1]
+            .iter()
+            .map(|art| parse(art).unwrap())
+            .collect::<Vec<_>>();
+        let word = Grid::from_fn(22, 12, |p| {
+            let (i, x) = (p.x as usize / 8, p.x as usize % 8);
+            x < 6 && p.y >= 1 && large[i].get(Point::new(x as i32, p.y - 1)) == Some(&true)
+        });
+        assert_eq!(read_grid(&word).unwrap(), "AXZ");
+    }
+
+    #[test]
+    fn unknown_letters() {
+        let err = read("##..##\n#....#\n#....#\n#....#\n#....#\n##..##").unwrap_err();
+        let message = err.to_string();
+        assert!(message.starts_with("could not read 2 of the letters in ??"));
+        assert!(message.contains("letter 2:\n##\n.#\n.#\n.#\n.#\n##"));
+
+        assert!(read("#\n#\n#").is_err());
+        assert!(read("....\n....").is_err());
+    }
+}
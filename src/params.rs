@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use failure::err_msg;
+use toml::{value::Table, Value};
+
+use Result;
+
+/// Puzzle parameters that can be overridden by name, e.g. from the command line.
+pub trait Parameters: Default {
+    fn set(&mut self, key: &str, value: &str) -> Result<()>;
+}
+
+/// Parameter values by name, as given by the user and not yet type checked.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Overrides(BTreeMap<String, String>);
+
+impl Overrides {
+    pub fn new() -> Overrides {
+        Overrides::default()
+    }
+
+    pub fn from_table(table: &Table) -> Overrides {
+        let values = table
+            .iter()
+            .map(|(k, v)| {
+                let v = match v {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                (k.clone(), v)
+            })
+            .collect();
+
+        Overrides(values)
+    }
+
+    pub fn insert<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) {
+        self.0.insert(key.into(), value.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Starts from the default parameters and applies every override.
+    pub fn apply<P: Parameters>(&self) -> Result<P> {
+        let mut params = P::default();
+        for (k, v) in &self.0 {
+            params
+                .set(k, v)
+                .map_err(|e| format_err!("invalid parameter {}={}: {}", k, v, e))?;
+        }
+
+        Ok(params)
+    }
+
+    /// For solvers without parameters, so overrides aren't silently ignored.
+    pub fn ensure_empty(&self) -> Result<()> {
+        match self.0.keys().next() {
+            Some(k) => bail!("unknown parameter {}: this day takes no parameters", k),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses a `key=value` pair as given on the command line.
+pub fn parse_pair(s: &str) -> Result<(String, String)> {
+    let mut it = s.splitn(2, '=');
+    let key = it.next().unwrap().trim();
+    let value = it
+        .next()
+        .ok_or_else(|| err_msg("expected a parameter of the form key=value"))?
+        .trim();
+
+    ensure!(!key.is_empty(), "empty parameter name in {}", s);
+    Ok((key.to_owned(), value.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Params {
+        steps: u32,
+    }
+
+    impl Parameters for Params {
+        fn set(&mut self, key: &str, value: &str) -> Result<()> {
+            match key {
+                "steps" => self.steps = value.parse()?,
+                other => bail!("unknown parameter: {}", other),
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn apply_overrides() {
+        let mut overrides = Overrides::new();
+        assert_eq!(overrides.apply::<Params>().unwrap(), Params { steps: 0 });
+
+        overrides.insert("steps", "12");
+        assert_eq!(overrides.apply::<Params>().unwrap(), Params { steps: 12 });
+
+        overrides.insert("steps", "twelve");
+        assert!(overrides.apply::<Params>().is_err());
+    }
+
+    #[test]
+    fn unknown_parameter() {
+        let mut overrides = Overrides::new();
+        overrides.insert("step", "12");
+        assert!(overrides.apply::<Params>().is_err());
+        assert!(overrides.ensure_empty().is_err());
+    }
+
+    #[test]
+    fn pairs() {
+        assert_eq!(
+            parse_pair("bursts=70").unwrap(),
+            ("bursts".to_owned(), "70".to_owned())
+        );
+        assert!(parse_pair("bursts").is_err());
+        assert!(parse_pair("=70").is_err());
+    }
+
+    #[test]
+    fn from_table() {
+        let table: Table = ::toml::from_str("bursts = 70\nname = \"x\"").unwrap();
+        let mut expected = Overrides::new();
+        expected.insert("bursts", "70");
+        expected.insert("name", "x");
+        assert_eq!(Overrides::from_table(&table), expected);
+    }
+}
@@ -0,0 +1,239 @@
+//! Nom parsers for the shapes that keep coming back in the inputs: integer
+//! lists, `a <-> b, c` adjacency lines, `p=<x,y,z>` vectors, `key: value`
+//! pairs and sections separated by blank lines.
+//!
+//! The days build their own parsers out of these, and run them with `parse`
+//! or `lines` so that every input fails the same way.
+
+use std::str::FromStr;
+
+use nom::{self, space, Context, IResult};
+
+use Result;
+
+pub use nom::types::CompleteStr as Input;
+
+/// How much of the remaining input to show when parsing fails.
+const SNIPPET_CHARS: usize = 20;
+
+/// A decimal integer with an optional sign, as any type that parses from one.
+pub fn integer<T: FromStr>(input: Input) -> IResult<Input, T> {
+    map_res!(
+        input,
+        recognize!(pair!(opt!(one_of!("+-")), nom::digit)),
+        |s: Input| s.parse::<T>()
+    )
+}
+
+/// Letters, digits and underscores.
+pub fn identifier(input: Input) -> IResult<Input, &str> {
+    map!(
+        input,
+        take_while1!(|c: char| c.is_alphanumeric() || c == '_'),
+        |s| *s
+    )
+}
+
+/// A comma, with optional spaces around it.
+pub fn comma(input: Input) -> IResult<Input, Input> {
+    delimited!(input, opt!(space), tag!(","), opt!(space))
+}
+
+named!(separator(Input) -> Input, alt!(comma | space));
+
+/// Integers separated by commas or spaces, as in `0 2 7 0` or `3, 4, 1`.
+pub fn integers<T: FromStr>(input: Input) -> IResult<Input, Vec<T>> {
+    separated_list!(input, separator, integer::<T>)
+}
+
+/// Identifiers separated by commas, as in `ktlj, cntj, xhth`.
+pub fn identifiers(input: Input) -> IResult<Input, Vec<&str>> {
+    separated_list!(input, comma, identifier)
+}
+
+/// Comma separated integers between angle brackets, as in `< 3,-1,0>`.
+pub fn vector<T: FromStr>(input: Input) -> IResult<Input, Vec<T>> {
+    delimited!(
+        input,
+        pair!(char!('<'), opt!(space)),
+        separated_list!(comma, integer::<T>),
+        pair!(opt!(space), char!('>'))
+    )
+}
+
+/// A vector with a name, as in `p=<1,2,3>`.
+pub fn labelled<T: FromStr>(input: Input) -> IResult<Input, (&str, Vec<T>)> {
+    separated_pair!(input, identifier, char!('='), vector::<T>)
+}
+
+/// A key and the rest of the line, as in `Begin in state: A`, without the
+/// spaces around either.
+pub fn key_value(input: Input) -> IResult<Input, (&str, &str)> {
+    do_parse!(
+        input,
+        key: take_till1!(|c| c == ':' || c == '\n') >>
+        char!(':') >>
+        value: call!(nom::not_line_ending) >>
+        (key.0.trim(), value.0.trim())
+    )
+}
+
+/// A node and its neighbours, as in `2 <-> 0, 3, 4`.
+pub fn adjacency<T: FromStr>(input: Input) -> IResult<Input, (T, Vec<T>)> {
+    separated_pair!(
+        input,
+        integer::<T>,
+        delimited!(opt!(space), tag!("<->"), opt!(space)),
+        separated_list!(comma, integer::<T>)
+    )
+}
+
+/// The parts of `s` between blank lines, without empty ones.
+pub fn sections(s: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+    let mut offset = 0;
+
+    for line in s.split('\n') {
+        if line.trim().is_empty() {
+            if let Some(first) = start.take() {
+                sections.push(&s[first..end]);
+            }
+        } else {
+            start = start.or(Some(offset));
+            end = offset + line.trim_right().len();
+        }
+
+        offset += line.len() + 1;
+    }
+
+    if let Some(first) = start {
+        sections.push(&s[first..end]);
+    }
+
+    sections
+}
+
+/// Runs `parser` over the whole of `s`, failing on anything left over.
+pub fn parse<'a, T, P>(s: &'a str, parser: P) -> Result<T>
+where
+    P: Fn(Input<'a>) -> IResult<Input<'a>, T>,
+{
+    match parser(Input(s)) {
+        Ok((rest, value)) => {
+            ensure!(rest.is_empty(), "unexpected {}", snippet(&rest));
+            Ok(value)
+        }
+        Err(e) => Err(describe(e)),
+    }
+}
+
+/// Runs `parser` over every line of `s`, skipping blank ones, and says which
+/// line it failed on.
+pub fn lines<'a, T, P>(s: &'a str, parser: P) -> Result<Vec<T>>
+where
+    P: Fn(Input<'a>) -> IResult<Input<'a>, T>,
+{
+    s.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            parse(line.trim(), &parser).map_err(|e| format_err!("line {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+fn describe(e: nom::Err<Input>) -> ::failure::Error {
+    match e {
+        nom::Err::Error(Context::Code(rest, kind))
+        | nom::Err::Failure(Context::Code(rest, kind)) => {
+            format_err!("expected {} at {}", kind.description(), snippet(&rest))
+        }
+        nom::Err::Incomplete(_) => format_err!("unexpected end of input"),
+    }
+}
+
+fn snippet(rest: &str) -> String {
+    if rest.is_empty() {
+        return "the end of input".to_string();
+    }
+
+    let mut shown: String = rest.chars().take(SNIPPET_CHARS).collect();
+    if shown.len() < rest.len() {
+        shown.push_str("...");
+    }
+
+    format!("{:?}", shown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(parse("-12", integer::<i32>).unwrap(), -12);
+        assert_eq!(parse("+7", integer::<i64>).unwrap(), 7);
+        assert!(parse("-1", integer::<u32>).is_err());
+        assert!(parse("12a", integer::<u32>).is_err());
+
+        assert_eq!(
+            parse("0\t2 7  0", integers::<u32>).unwrap(),
+            vec![0, 2, 7, 0]
+        );
+        assert_eq!(parse("3, 4,1", integers::<u8>).unwrap(), vec![3, 4, 1]);
+        assert!(parse("", integers::<u8>).unwrap().is_empty());
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(identifier(Input("pbga (66)")), Ok((Input(" (66)"), "pbga")));
+        assert_eq!(
+            parse("ktlj, cntj,xhth", identifiers).unwrap(),
+            vec!["ktlj", "cntj", "xhth"]
+        );
+    }
+
+    #[test]
+    fn shapes() {
+        assert_eq!(parse("< 3,-1, 0>", vector::<i64>).unwrap(), vec![3, -1, 0]);
+        assert_eq!(
+            parse("a=<-1,0,2>", labelled::<i64>).unwrap(),
+            ("a", vec![-1, 0, 2])
+        );
+        assert_eq!(
+            parse("2 <-> 0, 3, 4", adjacency::<u32>).unwrap(),
+            (2, vec![0, 3, 4])
+        );
+        assert_eq!(
+            parse("Begin in state: A.", key_value).unwrap(),
+            ("Begin in state", "A.")
+        );
+        assert_eq!(parse("6 : 4", key_value).unwrap(), ("6", "4"));
+    }
+
+    #[test]
+    fn blank_lines() {
+        let s = "\nfirst\nsection  \n\n \n\nsecond\n";
+        assert_eq!(sections(s), vec!["first\nsection", "second"]);
+        assert!(sections("\n\n").is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let err = parse("1 2 x", integers::<u32>).unwrap_err();
+        assert_eq!(err.to_string(), "unexpected \" x\"");
+
+        let err = parse("p=(1,2,3)", labelled::<i32>).unwrap_err();
+        assert_eq!(err.to_string(), "expected Char at \"(1,2,3)\"");
+
+        let err = lines("0 <-> 1\n\n1 <-> 0, two", adjacency::<u32>).unwrap_err();
+        assert_eq!(err.to_string(), "line 3: unexpected \", two\"");
+
+        assert_eq!(
+            lines("0 <-> 1\n1 <-> 0\n", adjacency::<u32>).unwrap(),
+            vec![(0, vec![1]), (1, vec![0])]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt;
+use std::time::Duration;
+
+use rayon::{self, ThreadPoolBuilder};
+
+use batch::{quietly, solve_guarded, with_timeout, Outcome};
+use params::Overrides;
+use Solver;
+
+/// A single run of a solver, on a thread pool of the given size.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Run {
+    pub threads: usize,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub runs: Vec<Run>,
+}
+
+impl Report {
+    /// Each different outcome, with the runs that produced it.
+    pub fn distinct(&self) -> Vec<(&Outcome, Vec<&Run>)> {
+        let mut groups: Vec<(&Outcome, Vec<&Run>)> = Vec::new();
+        for run in &self.runs {
+            match groups.iter().position(|(o, _)| **o == run.outcome) {
+                Some(i) => groups[i].1.push(run),
+                None => groups.push((&run.outcome, vec![run])),
+            }
+        }
+
+        groups
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.distinct().len() <= 1
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let distinct = self.distinct();
+        writeln!(
+            f,
+            "{} runs, {} distinct outcome{}",
+            self.runs.len(),
+            distinct.len(),
+            if distinct.len() == 1 { "" } else { "s" }
+        )?;
+
+        for (outcome, runs) in distinct {
+            let mut threads: Vec<usize> = runs.iter().map(|r| r.threads).collect();
+            threads.sort();
+            threads.dedup();
+            let threads: Vec<String> = threads.iter().map(|t| t.to_string()).collect();
+
+            writeln!(
+                f,
+                "\n{} runs with {} threads:",
+                runs.len(),
+                threads.join(", ")
+            )?;
+            match outcome {
+                Outcome::Solved(answers) => write!(f, "{}", answers)?,
+                Outcome::Failed(e) => writeln!(f, "ERROR: {}", e)?,
+                Outcome::Panicked(msg) => writeln!(f, "PANIC: {}", msg)?,
+                Outcome::TimedOut(secs) => writeln!(f, "TIMEOUT: after {}s", secs)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs `solver` on its own pool of `threads` threads, so anything it does
+/// with rayon is spread over exactly that many.
+pub fn solve_in_pool(
+    solver: Solver,
+    input: &str,
+    overrides: &Overrides,
+    threads: usize,
+    timeout: Option<Duration>,
+) -> Outcome {
+    let input = input.to_owned();
+    let overrides = overrides.clone();
+
+    with_timeout(timeout, move || {
+        match ThreadPoolBuilder::new().num_threads(threads).build() {
+            Ok(pool) => pool.install(|| solve_guarded(solver, &input, &overrides)),
+            Err(e) => Outcome::Failed(format!("failed to start {} threads: {}", threads, e)),
+        }
+    })
+}
+
+/// Runs `solver` `times` times, cycling through thread pools from a single
+/// thread up to at least twice as many as rayon uses by default.
+pub fn repeat(
+    solver: Solver,
+    input: &str,
+    overrides: &Overrides,
+    times: usize,
+    timeout: Option<Duration>,
+) -> Report {
+    let max_threads = (2 * rayon::current_num_threads()).max(4);
+
+    let runs = quietly(|| {
+        (0..times)
+            .map(|i| {
+                let threads = i % max_threads + 1;
+                let outcome = solve_in_pool(solver, input, overrides, threads, timeout);
+                debug!("run {} with {} threads: {:?}", i + 1, threads, outcome);
+                Run { threads, outcome }
+            })
+            .collect()
+    });
+
+    Report { runs }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use {solver, Answers, Result};
+
+    #[test]
+    fn deterministic() {
+        let day1 = solver(2017, 1).unwrap();
+        let report = repeat(day1, "1122", &Overrides::new(), 5, None);
+        assert_eq!(report.runs.len(), 5);
+        assert_eq!(report.runs[1].threads, 2);
+        assert!(report.is_deterministic());
+    }
+
+    #[test]
+    fn flaky() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        fn flaky(_: &str, _: &Overrides) -> Result<Answers> {
+            Ok(Answers::part1(CALLS.fetch_add(1, Ordering::SeqCst) % 2))
+        }
+
+        let report = repeat(flaky, "", &Overrides::new(), 4, None);
+        assert!(!report.is_deterministic());
+        assert_eq!(report.distinct().len(), 2);
+    }
+
+    #[test]
+    fn pool_size() {
+        fn threads(_: &str, _: &Overrides) -> Result<Answers> {
+            Ok(Answers::part1(rayon::current_num_threads()))
+        }
+
+        let outcome = solve_in_pool(threads, "", &Overrides::new(), 3, None);
+        assert_eq!(outcome, Outcome::Solved(Answers::part1(3)));
+    }
+}
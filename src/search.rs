@@ -0,0 +1,423 @@
+//! Shortest paths through state spaces too large to build up front.
+//!
+//! The states reachable from one another are given by a `neighbours`
+//! closure, and the search stops at the first state accepted by `goal`.
+//! Every state seen is kept together with the one it was reached from, so
+//! the whole path can be put back together at the end.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use fnv::FnvHashSet;
+use rayon::prelude::*;
+
+/// What moving between two states can cost.
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<C: Copy + Ord + Default + Add<Output = C>> Cost for C {}
+
+/// Remembers which states have been seen already.
+pub trait Visited<S> {
+    /// Marks `state` as seen, returning false if it already was.
+    fn insert(&mut self, state: &S) -> bool;
+
+    fn contains(&self, state: &S) -> bool;
+}
+
+impl<S: Clone + Eq + Hash> Visited<S> for FnvHashSet<S> {
+    fn insert(&mut self, state: &S) -> bool {
+        if FnvHashSet::contains(self, state) {
+            false
+        } else {
+            FnvHashSet::insert(self, state.clone())
+        }
+    }
+
+    fn contains(&self, state: &S) -> bool {
+        FnvHashSet::contains(self, state)
+    }
+}
+
+/// Treats states with the same key as the same, for instance to only keep
+/// one of several states that are equivalent.
+pub struct ByKey<K, F> {
+    seen: FnvHashSet<K>,
+    key: F,
+}
+
+impl<K: Eq + Hash, F> ByKey<K, F> {
+    pub fn new(key: F) -> ByKey<K, F> {
+        ByKey {
+            seen: FnvHashSet::default(),
+            key,
+        }
+    }
+}
+
+impl<S, K: Eq + Hash, F: Fn(&S) -> K> Visited<S> for ByKey<K, F> {
+    fn insert(&mut self, state: &S) -> bool {
+        self.seen.insert((self.key)(state))
+    }
+
+    fn contains(&self, state: &S) -> bool {
+        self.seen.contains(&(self.key)(state))
+    }
+}
+
+/// The states from the start to a goal, both included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<S, C> {
+    pub states: Vec<S>,
+    pub cost: C,
+}
+
+impl<S, C> Path<S, C> {
+    /// Number of moves, one less than the number of states.
+    pub fn steps(&self) -> usize {
+        self.states.len() - 1
+    }
+
+    pub fn end(&self) -> &S {
+        &self.states[self.states.len() - 1]
+    }
+}
+
+/// Every state seen by a search, with the index of the one it was reached
+/// from.
+struct Tree<S, C> {
+    nodes: Vec<(S, Option<usize>, C)>,
+}
+
+impl<S: Clone, C: Copy> Tree<S, C> {
+    fn new(root: S, cost: C) -> Tree<S, C> {
+        Tree {
+            nodes: vec![(root, None, cost)],
+        }
+    }
+
+    fn add(&mut self, state: S, parent: usize, cost: C) -> usize {
+        self.nodes.push((state, Some(parent), cost));
+        self.nodes.len() - 1
+    }
+
+    fn state(&self, i: usize) -> &S {
+        &self.nodes[i].0
+    }
+
+    fn cost(&self, i: usize) -> C {
+        self.nodes[i].2
+    }
+
+    fn path(&self, mut i: usize) -> Path<S, C> {
+        let cost = self.cost(i);
+        let mut states = vec![self.state(i).clone()];
+        while let Some(parent) = self.nodes[i].1 {
+            states.push(self.state(parent).clone());
+            i = parent;
+        }
+
+        states.reverse();
+        Path { states, cost }
+    }
+}
+
+/// The path with the fewest steps to a goal.
+pub fn bfs<S, N, I, G>(start: S, neighbours: N, goal: G) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    bfs_with(start, neighbours, goal, FnvHashSet::default())
+}
+
+/// Like `bfs`, deciding which states were seen already with `visited`.
+pub fn bfs_with<S, N, I, G, V>(
+    start: S,
+    mut neighbours: N,
+    mut goal: G,
+    mut visited: V,
+) -> Option<Path<S, usize>>
+where
+    S: Clone,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+    V: Visited<S>,
+{
+    if goal(&start) {
+        return Some(Tree::new(start, 0).path(0));
+    }
+
+    visited.insert(&start);
+    let mut tree = Tree::new(start, 0);
+    let mut queue = VecDeque::new();
+    queue.push_back(0);
+
+    while let Some(i) = queue.pop_front() {
+        let cost = tree.cost(i) + 1;
+        for next in neighbours(tree.state(i)) {
+            if !visited.insert(&next) {
+                continue;
+            }
+
+            let found = goal(&next);
+            let j = tree.add(next, i, cost);
+            if found {
+                return Some(tree.path(j));
+            }
+
+            queue.push_back(j);
+        }
+    }
+
+    None
+}
+
+/// Like `bfs`, but finds the neighbours of all the states at the same
+/// distance in parallel, which pays off when that is the expensive part.
+pub fn bfs_parallel<S, N, G>(start: S, neighbours: N, goal: G) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash + Send + Sync,
+    N: Fn(&S) -> Vec<S> + Sync,
+    G: Fn(&S) -> bool + Sync,
+{
+    if goal(&start) {
+        return Some(Tree::new(start, 0).path(0));
+    }
+
+    let mut visited = FnvHashSet::default();
+    visited.insert(start.clone());
+    let mut tree = Tree::new(start, 0);
+    let mut frontier = vec![0];
+    let mut distance = 0;
+
+    while !frontier.is_empty() {
+        distance += 1;
+        let expanded: Vec<(usize, Vec<(S, bool)>)> = {
+            let tree = &tree;
+            frontier
+                .par_iter()
+                .map(|&i| {
+                    let next = neighbours(tree.state(i))
+                        .into_iter()
+                        .map(|s| {
+                            let found = goal(&s);
+                            (s, found)
+                        })
+                        .collect();
+                    (i, next)
+                })
+                .collect()
+        };
+
+        // Adding the states in the order of the frontier finds the same path
+        // as `bfs` would
+        let mut next_frontier = Vec::new();
+        for (i, next) in expanded {
+            for (state, found) in next {
+                if !visited.insert(state.clone()) {
+                    continue;
+                }
+
+                let j = tree.add(state, i, distance);
+                if found {
+                    return Some(tree.path(j));
+                }
+
+                next_frontier.push(j);
+            }
+        }
+
+        frontier = next_frontier;
+    }
+
+    None
+}
+
+/// Every state reachable from `start`, with the fewest steps to it, closest
+/// first.
+pub fn reachable<S, N, I>(start: S, mut neighbours: N) -> Vec<(S, usize)>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited = FnvHashSet::default();
+    visited.insert(start.clone());
+    let mut queue = VecDeque::new();
+    let mut order = Vec::new();
+
+    queue.push_back((start, 0));
+    while let Some((state, distance)) = queue.pop_front() {
+        for next in neighbours(&state) {
+            if !visited.contains(&next) {
+                visited.insert(next.clone());
+                queue.push_back((next, distance + 1));
+            }
+        }
+
+        order.push((state, distance));
+    }
+
+    order
+}
+
+/// The cheapest path to a goal, where every move has a cost.
+pub fn dijkstra<S, C, N, I, G>(start: S, neighbours: N, goal: G) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+{
+    astar_with(
+        start,
+        neighbours,
+        |_| C::default(),
+        goal,
+        FnvHashSet::default(),
+    )
+}
+
+/// The cheapest path to a goal, trying the states that `heuristic` thinks
+/// are closest to one first. The heuristic must never overestimate the cost
+/// left, nor drop by more than the cost of a move.
+pub fn astar<S, C, N, I, H, G>(start: S, neighbours: N, heuristic: H, goal: G) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    astar_with(start, neighbours, heuristic, goal, FnvHashSet::default())
+}
+
+/// Like `astar`, deciding which states were seen already with `visited`.
+pub fn astar_with<S, C, N, I, H, G, V>(
+    start: S,
+    mut neighbours: N,
+    mut heuristic: H,
+    mut goal: G,
+    mut visited: V,
+) -> Option<Path<S, C>>
+where
+    S: Clone,
+    C: Cost,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+    V: Visited<S>,
+{
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((heuristic(&start), 0)));
+    let mut tree = Tree::new(start, C::default());
+
+    // States are only done with once they come off the heap, as a cheaper
+    // way to them may still turn up until then
+    while let Some(Reverse((_, i))) = heap.pop() {
+        if !visited.insert(tree.state(i)) {
+            continue;
+        }
+
+        if goal(tree.state(i)) {
+            return Some(tree.path(i));
+        }
+
+        let cost = tree.cost(i);
+        for (next, step) in neighbours(tree.state(i)) {
+            if visited.contains(&next) {
+                continue;
+            }
+
+            let estimate = cost + step + heuristic(&next);
+            let j = tree.add(next, i, cost + step);
+            heap.push(Reverse((estimate, j)));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use geom::Point;
+
+    /// The office maze from 2016 day 13, walls where the formula has an odd
+    /// number of bits set.
+    fn open(p: Point, favourite: i32) -> bool {
+        let Point { x, y } = p;
+        x >= 0
+            && y >= 0
+            && (x * x + 3 * x + 2 * x * y + y + y * y + favourite).count_ones() % 2 == 0
+    }
+
+    fn moves(p: &Point) -> Vec<Point> {
+        p.neighbours4().filter(|&n| open(n, 10)).collect()
+    }
+
+    #[test]
+    fn mazes() {
+        let start = Point::new(1, 1);
+        let target = Point::new(7, 4);
+
+        let path = bfs(start, moves, |&p| p == target).unwrap();
+        assert_eq!((path.steps(), path.cost), (11, 11));
+        assert_eq!((path.states[0], *path.end()), (start, target));
+        assert!(path.states.windows(2).all(|w| w[0].manhattan(w[1]) == 1));
+
+        let parallel = bfs_parallel(start, moves, |&p| p == target).unwrap();
+        assert_eq!(parallel, path);
+
+        let weighted = |p: &Point| moves(p).into_iter().map(|n| (n, 1));
+        let cheapest = dijkstra(start, weighted, |&p| p == target).unwrap();
+        assert_eq!(cheapest.cost, 11);
+        let guided = astar(start, weighted, |p| p.manhattan(target), |&p| p == target).unwrap();
+        assert_eq!(guided.cost, 11);
+
+        let near = reachable(start, moves)
+            .into_iter()
+            .filter(|&(_, d)| d <= 2)
+            .count();
+        assert_eq!(near, 5);
+
+        // A wall, and the start is in a closed off part of the maze
+        assert_eq!(bfs(start, moves, |&p| p == Point::new(1, 0)), None);
+    }
+
+    #[test]
+    fn costs() {
+        // Going 0 -> 1 -> 3 is cheaper than going straight to 3
+        let arcs = |&s: &u32| match s {
+            0 => vec![(1, 2), (3, 10), (2, 1)],
+            1 => vec![(3, 2)],
+            2 => vec![(1, 4)],
+            _ => vec![],
+        };
+
+        let path = dijkstra(0, arcs, |&s| s == 3).unwrap();
+        assert_eq!((path.states, path.cost), (vec![0, 1, 3], 4));
+        assert_eq!(dijkstra(0, arcs, |&s| s == 0).unwrap().steps(), 0);
+        assert_eq!(dijkstra(0, arcs, |&s| s == 4), None);
+    }
+
+    #[test]
+    fn visited_by_key() {
+        // Numbers that only count as new if their last digit is
+        let next = |&n: &u32| vec![n + 1, n + 10];
+        let path = bfs_with(0, next, |&n| n == 19, ByKey::new(|&n: &u32| n % 10));
+        assert_eq!(path, None);
+
+        let path = bfs_with(0, next, |&n| n == 9, ByKey::new(|&n: &u32| n % 10)).unwrap();
+        assert_eq!(path.steps(), 9);
+    }
+}
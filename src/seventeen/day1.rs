@@ -1,6 +1,6 @@
 use failure::*;
 
-use super::Result;
+use super::{Answers, Result};
 
 pub fn parse(input: &str) -> Result<Vec<u32>> {
     input
@@ -24,14 +24,12 @@ pub fn reverse_captcha_half(xs: &[u32]) -> u32 {
         .sum()
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let parsed = parse(&input)?;
+pub fn solve(input: &str) -> Result<Answers> {
+    let parsed = parse(input)?;
     let first = reverse_captcha(&parsed);
     let second = reverse_captcha_half(&parsed);
 
-    println!("Day 1:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 use std::fmt;
 
-use super::Result;
+use super::{Answers, Result};
 
 pub struct HexSlice<'a>(&'a [u8]);
 
@@ -64,12 +64,10 @@ pub fn knothash(input: &str) -> Vec<u8> {
         .collect()
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let second = knothash(&input);
+pub fn solve(input: &str) -> Result<Answers> {
+    let second = knothash(input);
 
-    println!("Day 10:\nPart 2: {}\n", HexSlice::new(&second));
-    Ok(())
+    Ok(Answers::part2(second))
 }
 
 pub fn check_knothash(input: &str, expected: &str) {
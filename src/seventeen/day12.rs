@@ -1,66 +1,71 @@
-use fnv::{FnvHashMap, FnvHashSet};
+use std::collections::BTreeSet;
 
-use super::Result;
+use rand::prng::XorShiftRng;
+use rand::Rng;
 
-type Graph = FnvHashMap<u32, Vec<u32>>;
+use super::{Answers, Result};
+use graph::{self, Adjacency};
+use parsers::{self, adjacency};
+
+type Graph = Adjacency<u32>;
 
 pub fn parse_graph(input: &str) -> Result<Graph> {
-    input
-        .trim()
-        .lines()
-        .map(|l| {
-            let mut it = l.split("<->");
-            let program = it.next().expect("left").trim().parse()?;
-            let neighbors: Vec<u32> = it
-                .next()
-                .expect("right")
-                .trim()
-                .split(", ")
-                .map(|s| s.parse().map_err(Into::into))
-                .collect::<Result<_>>()?;
-
-            Ok((program, neighbors))
-        })
-        .collect()
-}
+    let mut graph = Graph::new();
 
-pub fn process_pipegraph(mut graph: Graph) -> (u32, u32) {
-    let mut size = 0;
-    let mut count = 0;
-    let n = graph.keys().len() as u32;
+    for (program, neighbours) in parsers::lines(input, adjacency)? {
+        graph.add_node(program);
 
-    for i in 0..n {
-        if !graph.contains_key(&i) {
-            continue;
+        for neighbour in neighbours {
+            graph.add_edge(program, neighbour);
         }
+    }
 
-        let mut stack = vec![i];
-        let mut connected = FnvHashSet::default();
-        while let Some(cur) = stack.pop() {
-            connected.insert(cur);
+    Ok(graph)
+}
 
-            if let Some(ks) = graph.remove(&cur) {
-                stack.extend(ks);
-            }
-        }
+/// The size of the group containing program 0, which is empty if there is no
+/// such program, and the number of groups.
+pub fn process_pipegraph(graph: &Graph) -> (u32, u32) {
+    let groups = graph::components(graph);
+    let size = groups.iter().find(|g| g.contains(&0)).map_or(0, Vec::len);
 
-        if connected.contains(&0) {
-            size = connected.len() as u32;
-        }
+    (size as u32, groups.len() as u32)
+}
 
-        count += 1;
-    }
+pub fn solve(input: &str) -> Result<Answers> {
+    let graph = parse_graph(input)?;
+    let (first, second) = process_pipegraph(&graph);
 
-    (size, count)
+    Ok(Answers::new(first, second))
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let graph = parse_graph(&input)?;
-    let (first, second) = process_pipegraph(graph);
+/// A pipe graph of `size` programs, each with between one and three pipes of
+/// its own, and pipes going both ways.
+pub fn generate(rng: &mut XorShiftRng, size: usize) -> String {
+    let size = size.max(1);
+    let mut neighbors = vec![BTreeSet::new(); size];
+
+    for i in 0..size {
+        for _ in 0..rng.gen_range(0, 3) {
+            let j = rng.gen_range(0, size);
+            neighbors[i].insert(j);
+            neighbors[j].insert(i);
+        }
+    }
+
+    neighbors
+        .iter_mut()
+        .enumerate()
+        .map(|(i, ns)| {
+            if ns.is_empty() {
+                ns.insert(i);
+            }
 
-    println!("Day 12:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+            let ns: Vec<String> = ns.iter().map(|n| n.to_string()).collect();
+            format!("{} <-> {}", i, ns.join(", "))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
 #[cfg(test)]
@@ -72,6 +77,25 @@ mod tests {
         let graph = parse_graph(
             "0 <-> 2\n1 <-> 1\n2 <-> 0, 3, 4\n3 <-> 2, 4\n4 <-> 2, 3, 6\n5 <-> 6\n6 <-> 4, 5",
         ).unwrap();
-        assert_eq!(process_pipegraph(graph), (6, 2));
+        assert_eq!(process_pipegraph(&graph), (6, 2));
+    }
+
+    #[test]
+    fn test_sparse_ids() {
+        let graph = parse_graph("5 <-> 6\n6 <-> 5").unwrap();
+        assert_eq!(process_pipegraph(&graph), (0, 1));
+
+        let graph = parse_graph("0 <-> 7\n7 <-> 0\n12 <-> 12").unwrap();
+        assert_eq!(process_pipegraph(&graph), (2, 2));
+
+        assert_eq!(process_pipegraph(&parse_graph("").unwrap()), (0, 0));
+    }
+
+    #[test]
+    fn test_generated() {
+        let graph = parse_graph(&generate(&mut ::seeded_rng(12), 500)).unwrap();
+        assert_eq!(graph.len(), 500);
+        let (size, count) = process_pipegraph(&graph);
+        assert!(size >= 1 && count >= 1);
     }
 }
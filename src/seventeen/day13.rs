@@ -1,4 +1,8 @@
-use super::Result;
+use nom::space;
+
+use super::{Answers, Result};
+use numtheory;
+use parsers::{self, integer, Input};
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct Layer {
@@ -6,25 +10,34 @@ pub struct Layer {
     range: u32,
 }
 
-impl Layer {
-    fn from_str(s: &str) -> Result<Layer> {
-        let mut it = s.split(": ");
-        let depth = it.next().unwrap().parse()?;
-        let range = it.next().unwrap().parse()?;
+named!(
+    layer(Input) -> Layer,
+    do_parse!(
+        depth: integer >>
+        tag!(":") >>
+        opt!(space) >>
+        range: verify!(integer, |r: u32| r > 0) >>
+        (Layer { depth, range })
+    )
+);
 
-        Ok(Layer { depth, range })
+impl Layer {
+    /// Number of picoseconds for the scanner to come back to the top, which
+    /// a scanner with a range of 1 never leaves.
+    fn period(&self) -> u32 {
+        (2 * (self.range - 1)).max(1)
     }
 }
 
 pub fn parse_layers(s: &str) -> Result<Vec<Layer>> {
-    s.trim().lines().map(Layer::from_str).collect()
+    parsers::lines(s, layer)
 }
 
 pub fn default_severity(layers: &[Layer]) -> u32 {
     layers
         .iter()
         .map(|layer| {
-            if layer.depth % (2 * (layer.range - 1)) == 0 {
+            if layer.depth % layer.period() == 0 {
                 layer.depth * layer.range
             } else {
                 0
@@ -33,24 +46,23 @@ pub fn default_severity(layers: &[Layer]) -> u32 {
         .sum()
 }
 
-pub fn delay(layers: &[Layer]) -> u32 {
-    (0..)
-        .find(|delay| {
-            !layers
-                .iter()
-                .any(|layer| (delay + layer.depth) % (2 * (layer.range - 1)) == 0)
-        })
-        .unwrap()
+/// The packet is caught by a layer whenever `delay + depth` is a multiple of
+/// its period, so each layer rules out one residue of the delay.
+pub fn delay(layers: &[Layer]) -> Option<u32> {
+    let exclusions: Vec<(i64, i64)> = layers
+        .iter()
+        .map(|layer| (-i64::from(layer.depth), i64::from(layer.period())))
+        .collect();
+
+    numtheory::crt_excluding(&[], &exclusions).map(|d| d as u32)
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let layers = parse_layers(&input)?;
+pub fn solve(input: &str) -> Result<Answers> {
+    let layers = parse_layers(input)?;
     let first = default_severity(&layers);
-    let second = delay(&layers);
+    let second = delay(&layers).ok_or_else(|| format_err!("the packet is always caught"))?;
 
-    println!("Day 13:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
@@ -68,6 +80,19 @@ mod tests {
     #[test]
     fn test_second() {
         let layers = parse_layers(IN).unwrap();
-        assert_eq!(delay(&layers), 10);
+        assert_eq!(delay(&layers), Some(10));
+    }
+
+    #[test]
+    fn test_short_ranges() {
+        let layers = parse_layers("0: 1\n3: 2").unwrap();
+        assert_eq!(default_severity(&layers), 0);
+        assert_eq!(delay(&layers), None);
+
+        let layers = parse_layers("2: 1").unwrap();
+        assert_eq!(default_severity(&layers), 2);
+        assert_eq!(delay(&layers), None);
+
+        assert!(parse_layers("1: 0").is_err());
     }
 }
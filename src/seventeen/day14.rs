@@ -1,76 +1,51 @@
-use std::collections::VecDeque;
-
 use bit_vec::BitVec;
-use fnv::FnvHashSet;
 
 use super::day10::knothash;
-use super::Result;
-
-type Point = (i32, i32);
-type Grid = FnvHashSet<Point>;
+use super::{Answers, Result};
+use bitgrid::BitGrid;
+use image::{Image, Rgb};
 
-pub fn parse_grid(input: &str) -> Grid {
-    let mut grid = FnvHashSet::default();
+pub fn parse_grid(input: &str) -> BitGrid {
+    let rows: Vec<BitVec> = (0..128)
+        .map(|y| BitVec::from_bytes(&knothash(&format!("{}-{}", input.trim(), y))))
+        .collect();
 
-    for y in 0..128 {
-        let bytes = knothash(&format!("{}-{}", input.trim(), y));
-        let bits = BitVec::from_bytes(&bytes);
-        grid.extend(
-            bits.into_iter()
-                .enumerate()
-                .filter(|(_, b)| *b)
-                .map(|(i, _)| (i as i32, y as i32)),
-        );
-    }
-
-    grid
+    BitGrid::from_rows(rows).expect("knot hashes all have the same length")
 }
 
 pub fn squares_used(input: &str) -> u32 {
-    let mut squares = 0;
+    parse_grid(input).count() as u32
+}
 
-    for line in 0..128 {
-        for mut b in knothash(&format!("{}-{}", input, line)) {
-            while b > 0 {
-                if b & 1 == 1 {
-                    squares += 1;
-                }
+pub fn regions(grid: &BitGrid) -> u32 {
+    grid.regions().len() as u32
+}
 
-                b >>= 1;
-            }
-        }
-    }
+pub fn solve(input: &str) -> Result<Answers> {
+    let grid = parse_grid(input);
+    let first = grid.count();
+    let second = regions(&grid);
 
-    squares
+    Ok(Answers::new(first, second))
 }
 
-pub fn regions(mut grid: Grid) -> u32 {
-    let mut regions = 0;
-    let mut queue = VecDeque::new();
+/// The used squares of the disk, each region in a colour of its own.
+pub fn render(input: &str) -> Result<Image> {
+    let grid = parse_grid(input);
+    let mut image = Image::new(grid.width(), grid.height(), Rgb::BLACK);
 
-    while let Some(&k) = grid.iter().next() {
-        grid.remove(&k);
-        regions += 1;
-        queue.push_back(k);
-        while let Some((x, y)) = queue.pop_front() {
-            queue.extend(grid.take(&(x - 1, y)));
-            queue.extend(grid.take(&(x, y - 1)));
-            queue.extend(grid.take(&(x + 1, y)));
-            queue.extend(grid.take(&(x, y + 1)));
+    for (i, region) in grid.regions().iter().enumerate() {
+        let colour = Rgb(
+            (96 + i * 67 % 160) as u8,
+            (96 + i * 113 % 160) as u8,
+            (96 + i * 29 % 160) as u8,
+        );
+        for p in region.ones() {
+            image.set(p, colour);
         }
     }
 
-    regions
-}
-
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let grid = parse_grid(&input);
-    let first = squares_used(&input);
-    let second = regions(grid);
-
-    println!("Day 14:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(image.scale(4))
 }
 
 #[cfg(test)]
@@ -86,6 +61,6 @@ mod tests {
     #[test]
     fn test_second() {
         let input = parse_grid("flqrgnkx");
-        assert_eq!(regions(input), 1242);
+        assert_eq!(regions(&input), 1242);
     }
 }
@@ -1,8 +1,13 @@
-use super::Result;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use super::{Answers, Parameters, Result};
+use numtheory::{modpow, mulmod};
 
 const A: u64 = 16_807;
 const B: u64 = 48_271;
 const DIV: u64 = 2_147_483_647;
+/// How many pairs of part 1 are judged on one thread.
+const CHUNK: usize = 1 << 20;
 
 struct Generator {
     value: u64,
@@ -18,6 +23,13 @@ impl Generator {
             check,
         }
     }
+
+    /// Skips `steps` values at once, which only lines up with `next` when
+    /// every value passes the check.
+    fn jump(mut self, steps: u64) -> Self {
+        self.value = mulmod(self.value, modpow(self.factor, steps, DIV), DIV);
+        self
+    }
 }
 
 impl Iterator for Generator {
@@ -50,26 +62,63 @@ fn parse(s: &str) -> Result<(u64, u64)> {
     Ok((nums[0], nums[1]))
 }
 
-pub fn first(a: u64, b: u64) -> u32 {
-    let a = Generator::new(a, A, 1);
-    let b = Generator::new(b, B, 1);
-    a.zip(b).take(40_000_000).filter(|&(a, b)| a == b).count() as u32
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Params {
+    /// Number of pairs the judge considers in part 1
+    pub part1_pairs: usize,
+    /// Number of pairs the judge considers in part 2
+    pub part2_pairs: usize,
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params {
+            part1_pairs: 40_000_000,
+            part2_pairs: 5_000_000,
+        }
+    }
 }
 
-pub fn second(a: u64, b: u64) -> u32 {
+impl Parameters for Params {
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "part1_pairs" => self.part1_pairs = value.parse()?,
+            "part2_pairs" => self.part2_pairs = value.parse()?,
+            other => bail!("unknown parameter: {}", other),
+        }
+
+        Ok(())
+    }
+}
+
+/// Judges the pairs in chunks, with both generators jumping ahead to the
+/// start of each.
+pub fn first(a: u64, b: u64, pairs: usize) -> u32 {
+    let chunks = (pairs + CHUNK - 1) / CHUNK;
+    (0..chunks)
+        .into_par_iter()
+        .map(|i| {
+            let start = i * CHUNK;
+            let a = Generator::new(a, A, 1).jump(start as u64);
+            let b = Generator::new(b, B, 1).jump(start as u64);
+            let len = CHUNK.min(pairs - start);
+            a.zip(b).take(len).filter(|&(a, b)| a == b).count() as u32
+        })
+        .sum()
+}
+
+pub fn second(a: u64, b: u64, pairs: usize) -> u32 {
     let a = Generator::new(a, A, 4);
     let b = Generator::new(b, B, 8);
-    a.zip(b).take(5_000_000).filter(|&(a, b)| a == b).count() as u32
+    a.zip(b).take(pairs).filter(|&(a, b)| a == b).count() as u32
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let (a, b) = parse(&input)?;
-    let first = first(a, b);
-    let second = second(a, b);
+pub fn solve(input: &str, params: &Params) -> Result<Answers> {
+    let (a, b) = parse(input)?;
+    let first = first(a, b, params.part1_pairs);
+    let second = second(a, b, params.part2_pairs);
 
-    println!("Day 15:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
@@ -78,11 +127,26 @@ mod tests {
 
     #[test]
     fn test_first() {
-        assert_eq!(first(65, 8921), 588);
+        assert_eq!(first(65, 8921, 5), 1);
+        assert_eq!(first(65, 8921, 40_000_000), 588);
     }
 
     #[test]
     fn test_second() {
-        assert_eq!(second(65, 8921), 309);
+        assert_eq!(second(65, 8921, 1055), 0);
+        assert_eq!(second(65, 8921, 1056), 1);
+        assert_eq!(second(65, 8921, 5_000_000), 309);
+    }
+
+    #[test]
+    fn test_jump() {
+        let mut a = Generator::new(65, A, 1);
+        assert_eq!(a.nth(999), Generator::new(65, A, 1).jump(999).next());
+
+        let pairs = CHUNK + 1000;
+        let a = Generator::new(65, A, 1);
+        let b = Generator::new(8921, B, 1);
+        let expected = a.zip(b).take(pairs).filter(|&(a, b)| a == b).count() as u32;
+        assert_eq!(first(65, 8921, pairs), expected);
     }
 }
@@ -1,4 +1,5 @@
-use super::Result;
+use super::{Answers, Result};
+use cycle;
 
 use self::Dancemove::{P, S, X};
 
@@ -58,53 +59,44 @@ pub fn parse_routine(s: &str) -> Result<Vec<Dancemove>> {
         .collect::<Result<_>>()
 }
 
-pub fn dance(routine: &[Dancemove], reps: usize) -> String {
-    let order = &mut ABC;
+/// Performs the whole routine once, starting from `order`.
+fn perform(routine: &[Dancemove], order: &[char]) -> Vec<char> {
+    let mut order = order.to_vec();
     let n = order.len() as i32;
     let mut offset = 0;
-    let mut seen = vec![shift(offset, order)];
-    let mut result = String::new();
 
-    for i in 0..reps {
-        for m in routine {
-            match m {
-                S(k) => {
-                    offset = (offset + k) % n;
-                }
-                X(p1, p2) => {
-                    let p1 = ((((p1 - offset) % n) + n) % n) as usize;
-                    let p2 = ((((p2 - offset) % n) + n) % n) as usize;
-                    order.swap(p1, p2);
-                }
-                P(c1, c2) => {
-                    let p1 = order.iter().position(|c| c == c1).unwrap();
-                    let p2 = order.iter().position(|c| c == c2).unwrap();
-                    order.swap(p1, p2);
-                }
+    for m in routine {
+        match m {
+            S(k) => {
+                offset = (offset + k) % n;
+            }
+            X(p1, p2) => {
+                let p1 = ((((p1 - offset) % n) + n) % n) as usize;
+                let p2 = ((((p2 - offset) % n) + n) % n) as usize;
+                order.swap(p1, p2);
+            }
+            P(c1, c2) => {
+                let p1 = order.iter().position(|c| c == c1).unwrap();
+                let p2 = order.iter().position(|c| c == c2).unwrap();
+                order.swap(p1, p2);
             }
-        }
-
-        result = shift(offset, order);
-
-        if !seen.contains(&result) {
-            seen.push(result.clone());
-        } else {
-            result = seen.remove(reps % (i + 1));
-            break;
         }
     }
 
-    result
+    shift(offset, &order).chars().collect()
+}
+
+pub fn dance(routine: &[Dancemove], reps: usize) -> String {
+    let order = cycle::nth(ABC.to_vec(), |order| perform(routine, order), reps);
+    order.into_iter().collect()
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let routine = parse_routine(&input)?;
+pub fn solve(input: &str) -> Result<Answers> {
+    let routine = parse_routine(input)?;
     let first = dance(&routine, 1);
     let second = dance(&routine, 1_000_000_000);
 
-    println!("Day 16:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
@@ -118,4 +110,12 @@ mod tests {
         let routine = parse_routine(FULL).unwrap();
         assert_eq!(dance(&routine, 1), "ociedpjbmfnkhlga".to_owned())
     }
+    #[test]
+    fn test_second() {
+        let routine = parse_routine(FULL).unwrap();
+        assert_eq!(
+            dance(&routine, 1_000_000_000),
+            "gnflbkojhicpmead".to_owned()
+        )
+    }
 }
@@ -1,19 +1,31 @@
-use super::Result;
+use std::collections::VecDeque;
 
-pub fn spinlock(steps: usize) -> u32 {
-    let mut buf = Vec::with_capacity(2018);
+use rand::prng::XorShiftRng;
+use rand::Rng;
+
+use super::{Answers, Parameters, Result};
+use variants::{Suite, Variant};
+
+/// Inserts `limit` values into the buffer, returning it and the position of
+/// the last value inserted.
+fn fill_buffer(steps: usize, limit: usize) -> (Vec<u32>, usize) {
+    let mut buf = Vec::with_capacity(limit + 1);
     buf.push(0);
     let mut i = 0;
 
-    for k in 1..2018 {
+    for k in 1..=limit {
         i = ((i + steps) % k) + 1;
 
         buf.insert(i, k as u32);
     }
 
-    buf[i + 1]
+    (buf, i)
 }
 
+pub fn spinlock(steps: usize, limit: usize) -> u32 {
+    let (buf, i) = fill_buffer(steps, limit);
+    buf[(i + 1) % buf.len()]
+}
 
 pub fn angry_spinlock(steps: u32, limit: u32) -> u32 {
     let mut i = 0;
@@ -30,14 +42,99 @@ pub fn angry_spinlock(steps: u32, limit: u32) -> u32 {
     result
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let steps = input.parse()?;
-    let first = spinlock(steps);
-    let second = angry_spinlock(steps as u32, 50_000_000);
+/// Keeps the current position at the front of the buffer by rotating it.
+fn rotating_spinlock(steps: usize, limit: usize) -> u32 {
+    let mut buf = VecDeque::with_capacity(limit + 1);
+    buf.push_back(0);
+
+    for k in 1..=limit {
+        for _ in 0..steps % buf.len() {
+            let front = buf.pop_front().unwrap();
+            buf.push_back(front);
+        }
+
+        let front = buf.pop_front().unwrap();
+        buf.push_back(front);
+        buf.push_front(k as u32);
+    }
 
-    println!("Day 17:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    buf[1 % buf.len()]
+}
+
+fn value_after_zero(steps: usize, limit: usize) -> u32 {
+    let (buf, _) = fill_buffer(steps, limit);
+    let zero = buf.iter().position(|&v| v == 0).unwrap();
+    buf[(zero + 1) % buf.len()]
+}
+
+pub fn variants() -> Suite {
+    fn steps(input: &str) -> Result<usize> {
+        Ok(input.trim().parse()?)
+    }
+
+    Suite {
+        variants: vec![
+            Variant {
+                part: 1,
+                name: "insert",
+                solve: |input| Ok(spinlock(steps(input)?, 2017).into()),
+            },
+            Variant {
+                part: 1,
+                name: "rotate",
+                solve: |input| Ok(rotating_spinlock(steps(input)?, 2017).into()),
+            },
+            Variant {
+                part: 2,
+                name: "track",
+                solve: |input| Ok(angry_spinlock(steps(input)? as u32, 2017).into()),
+            },
+            Variant {
+                part: 2,
+                name: "buffer",
+                solve: |input| Ok(value_after_zero(steps(input)?, 2017).into()),
+            },
+        ],
+        examples: vec!["3"],
+        generate: Some(|rng: &mut XorShiftRng| rng.gen_range(1, 1000).to_string()),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Params {
+    /// Number of values inserted into the buffer in part 1
+    pub part1_insertions: usize,
+    /// Number of values inserted into the buffer in part 2
+    pub part2_insertions: u32,
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params {
+            part1_insertions: 2017,
+            part2_insertions: 50_000_000,
+        }
+    }
+}
+
+impl Parameters for Params {
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "part1_insertions" => self.part1_insertions = value.parse()?,
+            "part2_insertions" => self.part2_insertions = value.parse()?,
+            other => bail!("unknown parameter: {}", other),
+        }
+
+        Ok(())
+    }
+}
+
+pub fn solve(input: &str, params: &Params) -> Result<Answers> {
+    let steps = input.trim().parse()?;
+    let first = spinlock(steps, params.part1_insertions);
+    let second = angry_spinlock(steps as u32, params.part2_insertions);
+
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
@@ -46,11 +143,18 @@ mod tests {
 
     #[test]
     fn test_first() {
-        assert_eq!(spinlock(3), 638);
+        assert_eq!(spinlock(3, 9), 5);
+        assert_eq!(spinlock(3, 2017), 638);
     }
 
     #[test]
     fn test_second() {
         assert_eq!(angry_spinlock(3, 9), 9);
     }
+
+    #[test]
+    fn test_variants() {
+        let report = ::variants::compare(&variants(), 20, 17);
+        assert!(report.divergences.is_empty(), "{}", report);
+    }
 }
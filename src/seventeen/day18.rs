@@ -1,200 +1,29 @@
+use std::collections::VecDeque;
+use std::mem;
 use std::sync::Arc;
 
 use crossbeam::scope;
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use parking_lot::Mutex;
+use rand::prng::XorShiftRng;
+use rand::Rng;
 
-use self::Action::{Nothing, Store, Terminate};
-use self::Inst::{Add, Jgz, Mod, Mul, Rcv, Set, Snd};
-use super::Result;
+use super::{Answers, Result};
+use variants::{Suite, Variant};
+use vm::{self, Extension, Inst, Machine, Receive};
 
-type Memory = [i64; 256];
-
-#[derive(Clone, Copy, Debug)]
-pub struct Reg(u8);
-
-impl Reg {
-    fn parse(input: &str) -> Reg {
-        Reg(input.chars().next().expect("empty string") as u8)
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
-pub enum RegVal {
-    Reg(u8),
-    Val(i64),
-}
-
-impl RegVal {
-    fn parse(input: &str) -> RegVal {
-        use self::RegVal::{Reg, Val};
-
-        if let Ok(v) = input.parse::<i64>() {
-            Val(v)
-        } else {
-            let c = input.chars().next().expect("empty string");
-            Reg(c as u8)
-        }
-    }
-
-    fn eval(&self, mem: &[i64]) -> i64 {
-        use self::RegVal::*;
-
-        match *self {
-            Reg(reg) => mem[reg as usize],
-            Val(v) => v,
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
-pub enum Inst {
-    Set(Reg, RegVal),
-    Mul(Reg, RegVal),
-    Add(Reg, RegVal),
-    Mod(Reg, RegVal),
-    Jgz(RegVal, RegVal),
-    Snd(RegVal),
-    Rcv(Reg),
-}
+/// The instructions of the sound card.
+const DUET: &[&str] = &["set", "add", "mul", "mod", "jgz", "snd", "rcv"];
 
 pub fn parse(input: &str) -> Result<Vec<Inst>> {
-    let mut out = Vec::new();
-    for mut words in input.trim().lines().map(str::split_whitespace) {
-        let inst = words.next().expect("no instruction");
-        let inst = match inst {
-            "jgz" => {
-                let cond = RegVal::parse(words.next().expect("no register"));
-                let arg = RegVal::parse(words.next().expect("no argument"));
-                Inst::Jgz(cond, arg)
-            }
-            "snd" => {
-                let arg = RegVal::parse(words.next().expect("no argument"));
-                Inst::Snd(arg)
-            }
-            "rcv" => {
-                let reg = Reg::parse(words.next().expect("no argument"));
-                Inst::Rcv(reg)
-            }
-            bin => {
-                let reg = Reg::parse(words.next().expect("no register"));
-                let arg = RegVal::parse(words.next().expect("no argument"));
-                match bin {
-                    "set" => Inst::Set(reg, arg),
-                    "mul" => Inst::Mul(reg, arg),
-                    "add" => Inst::Add(reg, arg),
-                    "mod" => Inst::Mod(reg, arg),
-                    _ => bail!("unkown instruction: {}", bin),
-                }
-            }
-        };
-
-        out.push(inst);
-    }
-
-    Ok(out)
+    vm::parse(input, DUET)
 }
 
-#[derive(Clone)]
-enum Action {
-    Store(i64),
-    Nothing,
-    Terminate,
-}
-
-impl Action {
-    fn is_store(&self) -> bool {
-        match self {
-            Store(_) => true,
-            _ => false,
-        }
-    }
-}
-
-trait Channel {
-    fn id(&mut self) -> Option<u8> {
-        None
-    }
-
-    fn snd(&mut self, val: i64);
-
-    fn rcv(&mut self, cond: i64) -> Action;
-}
-
-#[derive(Clone)]
-struct Program<'a, C> {
-    mem: Memory,
-    inst: &'a [Inst],
-    ip: usize,
-    channel: C,
-}
-
-impl<'a, C> Program<'a, C>
-where
-    C: Channel,
-{
-    fn from_inst(inst: &'a [Inst], channel: C) -> Self {
-        let mut program = Program {
-            mem: [0; 256],
-            inst,
-            ip: 0,
-            channel,
-        };
-
-        if let Some(id) = program.channel.id() {
-            program.mem[b'p' as usize] = i64::from(id);
-        }
-
-        program
-    }
-
-    pub fn exec(&mut self) {
-        loop {
-            let it = self.inst[self.ip];
-
-            match it {
-                Set(Reg(reg), ref arg) => {
-                    self.mem[reg as usize] = arg.eval(&self.mem);
-                }
-                Mul(Reg(reg), ref arg) => {
-                    self.mem[reg as usize] *= arg.eval(&self.mem);
-                }
-                Add(Reg(reg), ref arg) => {
-                    self.mem[reg as usize] += arg.eval(&self.mem);
-                }
-                Mod(Reg(reg), ref arg) => {
-                    self.mem[reg as usize] %= arg.eval(&self.mem);
-                }
-                Jgz(ref cond, ref offset) => {
-                    let cond = cond.eval(&self.mem);
-                    if cond > 0 {
-                        let o = offset.eval(&self.mem);
-
-                        if o < 0 {
-                            self.ip = self.ip.checked_sub(-o as usize).expect("underflow");
-                        } else {
-                            self.ip = self.ip.checked_add(o as usize).expect("overflow");
-                        }
-                        continue;
-                    }
-                }
-                Snd(ref arg) => {
-                    let val = arg.eval(&self.mem);
-                    self.channel.snd(val);
-                }
-                Rcv(Reg(reg)) => {
-                    use self::{Nothing, Store, Terminate};
-                    let val = self.mem[reg as usize];
-                    match self.channel.rcv(val) {
-                        Store(val) => self.mem[reg as usize] = val,
-                        Nothing => (),
-                        Terminate => return,
-                    }
-                }
-            }
-            self.ip += 1;
-        }
-    }
+/// One of the two programs of part 2, which knows its id from register `p`.
+fn program<'a, E: Extension>(inst: &'a [Inst], id: u8, ext: E) -> Machine<'a, E> {
+    let mut machine = Machine::new(inst, ext);
+    machine.regs['p'] = i64::from(id);
+    machine
 }
 
 struct Duet {
@@ -207,43 +36,40 @@ impl Duet {
     }
 }
 
-impl Channel for Duet {
-    fn snd(&mut self, val: i64) {
+impl Extension for Duet {
+    fn send(&mut self, val: i64) {
         self.sent = val
     }
 
-    fn rcv(&mut self, cond: i64) -> Action {
+    fn receive(&mut self, cond: i64) -> Receive {
         if cond == 0 {
-            Nothing
+            Receive::Skip
         } else {
-            Terminate
+            Receive::Halt
         }
     }
 }
 
 pub fn duet(inst: &[Inst]) -> i64 {
-    let mut p = Program::from_inst(inst, Duet::new());
-    p.exec();
-    p.channel.sent
+    let mut p = Machine::new(inst, Duet::new());
+    p.run();
+    p.ext.sent
 }
 
 struct ThreadDuet {
-    id: u8,
     sent: u64,
-    sender: Sender<Action>,
-    receiver: Receiver<Action>,
+    sender: Sender<Receive>,
+    receiver: Receiver<Receive>,
     blocked: Arc<Mutex<bool>>,
 }
 
 impl ThreadDuet {
     fn new(
-        id: u8,
-        sender: Sender<Action>,
-        receiver: Receiver<Action>,
+        sender: Sender<Receive>,
+        receiver: Receiver<Receive>,
         blocked: Arc<Mutex<bool>>,
     ) -> Self {
         ThreadDuet {
-            id,
             sent: 0,
             sender,
             receiver,
@@ -252,25 +78,21 @@ impl ThreadDuet {
     }
 }
 
-impl Channel for ThreadDuet {
-    fn id(&mut self) -> Option<u8> {
-        Some(self.id)
-    }
-
-    fn snd(&mut self, val: i64) {
+impl Extension for ThreadDuet {
+    fn send(&mut self, val: i64) {
         self.sent += 1;
         let mut blocked = self.blocked.lock();
         *blocked = false;
-        let _ = self.sender.send(Store(val));
+        let _ = self.sender.send(Receive::Store(val));
     }
 
-    fn rcv(&mut self, _: i64) -> Action {
+    fn receive(&mut self, _: i64) -> Receive {
         {
             let mut blocked = self.blocked.lock();
             if self.receiver.is_empty() {
                 if *blocked {
-                    let _ = self.sender.send(Terminate);
-                    return Terminate;
+                    let _ = self.sender.send(Receive::Halt);
+                    return Receive::Halt;
                 } else {
                     *blocked = true;
                 }
@@ -279,7 +101,7 @@ impl Channel for ThreadDuet {
 
         match self.receiver.recv() {
             Some(action) => action,
-            _ => Terminate,
+            _ => Receive::Halt,
         }
     }
 }
@@ -291,25 +113,145 @@ pub fn thread_duet(inst: &[Inst]) -> u64 {
     let s0 = Arc::new(Mutex::new(false));
     let s1 = Arc::clone(&s0);
 
-    let mut p0 = Program::from_inst(inst, ThreadDuet::new(0, tx0, rx1, s0));
-    let mut p1 = Program::from_inst(inst, ThreadDuet::new(1, tx1, rx0, s1));
+    let mut p0 = program(inst, 0, ThreadDuet::new(tx0, rx1, s0));
+    let mut p1 = program(inst, 1, ThreadDuet::new(tx1, rx0, s1));
 
     scope(|scope| {
-        scope.spawn(|| p0.exec());
-        scope.spawn(|| p1.exec());
+        scope.spawn(|| p0.run());
+        scope.spawn(|| p1.run());
     });
 
-    p1.channel.sent
+    p1.ext.sent
+}
+
+/// A channel for running both programs in turn on a single thread.
+struct Mailbox {
+    sent: u64,
+    inbox: VecDeque<i64>,
+    outbox: Vec<i64>,
+}
+
+impl Mailbox {
+    fn new() -> Mailbox {
+        Mailbox {
+            sent: 0,
+            inbox: VecDeque::new(),
+            outbox: Vec::new(),
+        }
+    }
+}
+
+impl Extension for Mailbox {
+    fn send(&mut self, val: i64) {
+        self.sent += 1;
+        self.outbox.push(val);
+    }
+
+    /// Stops the program on an empty inbox, leaving it to retry the same
+    /// instruction when it is resumed.
+    fn receive(&mut self, _: i64) -> Receive {
+        match self.inbox.pop_front() {
+            Some(val) => Receive::Store(val),
+            None => Receive::Halt,
+        }
+    }
+}
+
+/// Like `thread_duet`, but alternates between the programs until neither of
+/// them can make progress.
+pub fn sequential_duet(inst: &[Inst]) -> u64 {
+    let mut p0 = program(inst, 0, Mailbox::new());
+    let mut p1 = program(inst, 1, Mailbox::new());
+
+    loop {
+        p0.run();
+        p1.run();
+
+        let to_p1 = mem::replace(&mut p0.ext.outbox, Vec::new());
+        let to_p0 = mem::replace(&mut p1.ext.outbox, Vec::new());
+        if to_p0.is_empty() && to_p1.is_empty() {
+            break;
+        }
+
+        p0.ext.inbox.extend(to_p0);
+        p1.ext.inbox.extend(to_p1);
+    }
+
+    p1.ext.sent
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let inst = parse(&input)?;
+/// A random program of about `len` instructions that always ends up waiting
+/// to receive, with small values so nothing overflows. The last `rcv` gets a
+/// non-zero register, so that part 1 stops there too.
+pub fn generate(rng: &mut XorShiftRng, len: usize) -> String {
+    const REGS: &[char] = &['a', 'b', 'c', 'd', 'e', 'p'];
+
+    let mut lines = Vec::new();
+
+    for i in 0..len {
+        let reg = *rng.choose(&REGS[..5]).unwrap();
+        let arg = if rng.gen_bool(0.5) {
+            rng.gen_range(-9, 10).to_string()
+        } else {
+            rng.choose(REGS).unwrap().to_string()
+        };
+
+        match rng.gen_range(0, 6) {
+            0 => lines.push(format!("set {} {}", reg, arg)),
+            1 => {
+                lines.push(format!("add {} {}", reg, arg));
+                lines.push(format!("mod {} {}", reg, rng.gen_range(2, 100)));
+            }
+            2 => {
+                lines.push(format!("mul {} {}", reg, arg));
+                lines.push(format!("mod {} {}", reg, rng.gen_range(2, 100)));
+            }
+            3 => lines.push(format!("snd {}", arg)),
+            4 => lines.push(format!("rcv {}", reg)),
+            _ => {
+                let offset = rng.gen_range(1, len - i + 1);
+                lines.push(format!("jgz {} {}", arg, offset));
+            }
+        }
+    }
+
+    lines.push("set a 1".to_owned());
+    lines.push("rcv a".to_owned());
+    lines.push("jgz 1 -1".to_owned());
+    lines.join("\n")
+}
+
+pub fn variants() -> Suite {
+    Suite {
+        variants: vec![
+            Variant {
+                part: 2,
+                name: "threads",
+                solve: |input| Ok(thread_duet(&parse(input)?).into()),
+            },
+            Variant {
+                part: 2,
+                name: "sequential",
+                solve: |input| Ok(sequential_duet(&parse(input)?).into()),
+            },
+        ],
+        examples: vec![
+            "snd 1\nsnd 2\nsnd p\nrcv a\nrcv b\nrcv c\nrcv d",
+            include_str!("../../data/d18-test"),
+        ],
+        generate: Some(|rng: &mut XorShiftRng| {
+            let len = rng.gen_range(1, 30);
+            generate(rng, len)
+        }),
+    }
+}
+
+pub fn solve(input: &str) -> Result<Answers> {
+    let inst = parse(input)?;
     let first = duet(&inst);
     let second = thread_duet(&inst);
 
-    println!("Day 18:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
@@ -338,4 +280,38 @@ mod tests {
         let inst = parse(FULL).unwrap();
         assert_eq!(thread_duet(&inst), 7112)
     }
+
+    #[test]
+    fn test_sequential() {
+        let inst = parse(FULL).unwrap();
+        assert_eq!(sequential_duet(&inst), 7112)
+    }
+
+    #[test]
+    fn test_generated_recovers() {
+        use std::time::Duration;
+
+        use batch::{self, Outcome};
+        use params::Overrides;
+
+        for seed in 0..50 {
+            let input = generate(&mut ::seeded_rng(seed), 15);
+            let outcome = batch::solve_timed(
+                |input, _| Ok(Answers::part1(duet(&parse(input)?))),
+                &input,
+                &Overrides::default(),
+                Some(Duration::from_secs(5)),
+            );
+            match outcome {
+                Outcome::Solved(_) => (),
+                other => panic!("seed {}: {:?}\n{}", seed, other, input),
+            }
+        }
+    }
+
+    #[test]
+    fn test_variants() {
+        let report = ::variants::compare(&variants(), 20, 18);
+        assert!(report.divergences.is_empty(), "{}", report);
+    }
 }
@@ -1,10 +1,12 @@
-use fnv::FnvHashMap;
+use super::{Answers, Result};
+use geom::Direction::South;
+use geom::Rotation::{Left, Right};
+use geom::{Direction, Point, Rotation};
+use grid::Grid;
+use image::{Image, Rgb};
+use tui::{self, Simulation};
 
-use super::Result;
-
-use self::Direction::{East, North, South, West};
 use self::Edge::{Corner, Letter, Line};
-use self::Rotation::{Left, Right};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Edge {
@@ -30,71 +32,14 @@ impl Edge {
     }
 }
 
-enum Rotation {
-    Left,
-    Right,
-}
-
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
-
-impl Direction {
-    fn left(&self) -> Direction {
-        match *self {
-            North => West,
-            South => East,
-            East => North,
-            West => South,
-        }
-    }
-
-    fn right(&self) -> Direction {
-        match *self {
-            North => East,
-            South => West,
-            East => South,
-            West => North,
-        }
-    }
-
-    fn turn(&self, r: &Rotation) -> Direction {
-        match r {
-            Right => self.right(),
-            Left => self.left(),
-        }
-    }
-}
-
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
-struct Node(i32, i32);
-
-impl Node {
-    fn neigh(&self, dir: Direction) -> Node {
-        let Node(x, y) = *self;
-        let (x, y) = match dir {
-            North => (x, y - 1),
-            South => (x, y + 1),
-            West => (x - 1, y),
-            East => (x + 1, y),
-        };
-
-        Node(x, y)
-    }
-}
-
 pub struct Path {
-    network: FnvHashMap<Node, Edge>,
-    current: Node,
+    network: Grid<Option<Edge>>,
+    current: Point,
     direction: Direction,
 }
 
 impl Path {
-    fn new(network: FnvHashMap<Node, Edge>, init: Node) -> Path {
+    fn new(network: Grid<Option<Edge>>, init: Point) -> Path {
         Path {
             network,
             current: init,
@@ -103,42 +48,37 @@ impl Path {
     }
 
     pub fn from_str(input: &str) -> Result<Path> {
-        let mut network = FnvHashMap::default();
-        let mut init = Node(0, 0);
-
-        for (y, line) in input.lines().enumerate() {
-            for (x, c) in line
-                .chars()
-                .enumerate()
-                .filter(|&(_, c)| !c.is_whitespace())
-            {
-                let edge = match c {
-                    '|' | '-' => Line,
-                    '+' => Corner,
-                    alpha @ 'A'...'Z' => Letter(alpha),
-                    other => bail!("unexpected character: {}", other),
-                };
-
-                let x = x as i32;
-                let y = y as i32;
-
-                if y == 0 {
-                    init = Node(x, y);
-                }
-
-                network.insert(Node(x, y), edge);
-            }
-        }
+        let network = Grid::from_chars(input, |c| {
+            let edge = match c {
+                ' ' => None,
+                '|' | '-' => Some(Line),
+                '+' => Some(Corner),
+                alpha @ 'A'...'Z' => Some(Letter(alpha)),
+                other => bail!("unexpected character: {}", other),
+            };
 
-        Ok(Path::new(network, init))
+            Ok(edge)
+        })?;
+
+        let x = network
+            .row(0)
+            .iter()
+            .position(Option::is_some)
+            .ok_or_else(|| format_err!("no path enters from the top"))?;
+
+        Ok(Path::new(network, Point::new(x as i32, 0)))
+    }
+
+    fn edge(&self, p: Point) -> Option<Edge> {
+        self.network.get(p).and_then(|&e| e)
     }
 
-    fn neigh(&self, r: &Rotation) -> Node {
-        self.current.neigh(self.direction.turn(r))
+    fn neigh(&self, r: Rotation) -> Point {
+        self.current.step(self.direction.turn(r))
     }
 
     fn next_node(&mut self) {
-        self.current = self.current.neigh(self.direction);
+        self.current = self.current.step(self.direction);
     }
 }
 
@@ -146,18 +86,15 @@ impl Iterator for Path {
     type Item = Edge;
 
     fn next(&mut self) -> Option<Edge> {
-        let &result = match self.network.get(&self.current) {
-            Some(n) => n,
-            None => return None,
-        };
+        let result = self.edge(self.current)?;
 
         if result.is_corner() {
-            let k = self.neigh(&Left);
+            let k = self.neigh(Left);
 
-            self.direction = if self.network.contains_key(&k) {
-                self.direction.turn(&Left)
+            self.direction = if self.edge(k).is_some() {
+                self.direction.turn(Left)
             } else {
-                self.direction.turn(&Right)
+                self.direction.turn(Right)
             };
         }
 
@@ -170,14 +107,75 @@ pub fn get_letters(path: impl Iterator<Item = Edge>) -> String {
     path.filter_map(|e| e.get_letter()).collect()
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let path: Vec<Edge> = Path::from_str(&input)?.collect();
+pub fn solve(input: &str) -> Result<Answers> {
+    let path: Vec<Edge> = Path::from_str(input)?.collect();
     let second = path.len();
     let first = get_letters(path.into_iter());
 
-    println!("Day 19:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
+}
+
+/// The network of tubes, with corners in white and letters in yellow.
+pub fn render(input: &str) -> Result<Image> {
+    let path = Path::from_str(input)?;
+    let image = Image::from_grid(&path.network, |edge| match edge {
+        None => Rgb::BLACK,
+        Some(Line) => Rgb::GREY,
+        Some(Corner) => Rgb::WHITE,
+        Some(Letter(_)) => Rgb::YELLOW,
+    });
+
+    Ok(image.scale(4))
+}
+
+/// The packet following the path, collecting letters on the way.
+struct Walker {
+    path: Path,
+    letters: String,
+}
+
+impl Simulation for Walker {
+    fn step(&mut self) -> bool {
+        match self.path.next() {
+            Some(edge) => {
+                self.letters.extend(edge.get_letter());
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn cell(&self, p: Point) -> char {
+        if p == self.path.current {
+            return tui::arrow(self.path.direction);
+        }
+
+        match self.path.edge(p) {
+            None => ' ',
+            Some(Line) => '.',
+            Some(Corner) => '+',
+            Some(Letter(c)) => c,
+        }
+    }
+
+    fn focus(&self) -> Point {
+        self.path.current
+    }
+
+    fn status(&self) -> Vec<(&'static str, String)> {
+        let Point { x, y } = self.path.current;
+        vec![
+            ("position", format!("{},{}", x, y)),
+            ("letters", self.letters.clone()),
+        ]
+    }
+}
+
+pub fn simulation(input: &str) -> Result<Box<Simulation>> {
+    Ok(Box::new(Walker {
+        path: Path::from_str(input)?,
+        letters: String::new(),
+    }))
 }
 
 #[cfg(test)]
@@ -1,17 +1,10 @@
 use std::iter;
 
-use super::Result;
+use super::{Answers, Result};
+use parsers::{self, integers};
 
 pub fn parse(input: &str) -> Result<Vec<Vec<u32>>> {
-    input
-        .trim()
-        .lines()
-        .map(|l| {
-            l.split_whitespace()
-                .map(|s| s.parse().map_err(Into::into))
-                .collect()
-        })
-        .collect()
+    parsers::lines(input, integers)
 }
 
 pub fn checksum(lines: &[Vec<u32>]) -> u32 {
@@ -49,14 +42,12 @@ pub fn divsum(lines: &[Vec<u32>]) -> u32 {
     result
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let parsed = parse(&input)?;
+pub fn solve(input: &str) -> Result<Answers> {
+    let parsed = parse(input)?;
     let first = checksum(&parsed);
     let second = divsum(&parsed);
 
-    println!("Day 2:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
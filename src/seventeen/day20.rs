@@ -2,7 +2,11 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::ops::AddAssign;
 
-use super::Result;
+use super::{Answers, Result};
+use animation::Recorder;
+use geom::Point;
+use image::Rgb;
+use parsers::{self, comma, labelled, Input};
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 struct Vector {
@@ -12,15 +16,11 @@ struct Vector {
 }
 
 impl Vector {
-    fn parse(input: &str) -> Result<Vector> {
-        let brackets: &[char] = &['<', '>', ' ', '\n', '\t'];
-        let mut it = input.trim_matches(brackets).split(',');
-
-        let x = it.next().unwrap().parse()?;
-        let y = it.next().unwrap().parse()?;
-        let z = it.next().unwrap().parse()?;
-
-        Ok(Vector { x, y, z })
+    fn from_slice(v: &[i64]) -> Option<Vector> {
+        match *v {
+            [x, y, z] => Some(Vector { x, y, z }),
+            _ => None,
+        }
     }
 
     fn sum(&self) -> usize {
@@ -58,20 +58,16 @@ pub struct Particle {
 }
 
 impl Particle {
-    fn parse(input: &str) -> Result<Particle> {
-        let vs: Vec<Vector> = input
-            .split(", ")
-            .map(|s| {
-                let s: String = s.chars().skip(2).collect();
-                Vector::parse(&s)
-            })
-            .collect::<Result<_>>()?;
-
-        let pos = vs[0];
-        let vel = vs[1];
-        let acc = vs[2];
-
-        Ok(Particle { pos, vel, acc })
+    /// The position, velocity and acceleration, in that order.
+    fn from_fields(fields: &[(&str, Vec<i64>)]) -> Option<Particle> {
+        match fields {
+            [("p", pos), ("v", vel), ("a", acc)] => Some(Particle {
+                pos: Vector::from_slice(pos)?,
+                vel: Vector::from_slice(vel)?,
+                acc: Vector::from_slice(acc)?,
+            }),
+            _ => None,
+        }
     }
 
     fn update(&mut self) {
@@ -80,8 +76,15 @@ impl Particle {
     }
 }
 
+named!(
+    particle(Input) -> Particle,
+    map_opt!(separated_list!(comma, labelled), |fields: Vec<_>| {
+        Particle::from_fields(&fields)
+    })
+);
+
 pub fn parse(input: &str) -> Result<Vec<Particle>> {
-    input.lines().map(Particle::parse).collect()
+    parsers::lines(input, particle)
 }
 
 pub fn first(particles: &[Particle]) -> usize {
@@ -93,28 +96,82 @@ pub fn first(particles: &[Particle]) -> usize {
         .unwrap()
 }
 
+fn collisions(particles: &[Particle]) -> HashMap<Vector, usize> {
+    let mut seen = HashMap::new();
+    for p in particles {
+        *seen.entry(p.pos).or_insert(0) += 1;
+    }
+
+    seen
+}
+
+/// Removes the particles that collide, then moves the others.
+fn tick(particles: &mut Vec<Particle>) {
+    let seen = collisions(particles);
+    particles.retain(|p| seen[&p.pos] < 2);
+    particles.iter_mut().for_each(|p| p.update());
+}
+
 pub fn second(mut particles: Vec<Particle>) -> usize {
-    for _ in 0..1000 {
-        let mut seen = HashMap::new();
-        for p in &particles {
-            *seen.entry(p.pos).or_insert(0) += 1;
-        }
+    (0..1000).for_each(|_| tick(&mut particles));
+    particles.len()
+}
 
-        particles.retain(|p| seen[&p.pos] < 2);
-        particles.iter_mut().for_each(|p| p.update());
+/// How many pixels the animation reaches on each side of the origin.
+const RADIUS: i64 = 100;
+
+/// How many units of space fit in a pixel for the whole swarm to start out
+/// within `RADIUS` of the origin.
+fn zoom(particles: &[Particle]) -> i64 {
+    let furthest = particles
+        .iter()
+        .map(|p| p.pos.x.abs().max(p.pos.y.abs()))
+        .max()
+        .unwrap_or(0);
+    furthest / RADIUS + 1
+}
+
+/// The particles still near the origin seen from above, `zoom` units to a
+/// pixel, in red when they are about to collide.
+fn draw(particles: &[Particle], zoom: i64) -> Vec<(Point, Rgb)> {
+    let seen = collisions(particles);
+    let reach = RADIUS * zoom;
+    particles
+        .iter()
+        .filter(|p| p.pos.x.abs() <= reach && p.pos.y.abs() <= reach)
+        .map(|p| {
+            let colour = if seen[&p.pos] < 2 {
+                Rgb::WHITE
+            } else {
+                Rgb::RED
+            };
+            let (x, y) = (p.pos.x / zoom, p.pos.y / zoom);
+            (Point::new(x as i32, y as i32), colour)
+        })
+        .collect()
+}
+
+/// The particles of part 2 flying apart and destroying each other, scaled
+/// down so that they start out fitting the animation and leave it as they
+/// speed away.
+pub fn animate(input: &str, recorder: &mut Recorder) -> Result<()> {
+    let mut particles = parse(input)?;
+    let zoom = zoom(&particles);
+    for _ in 0..1000 {
+        recorder.step(|| draw(&particles, zoom));
+        tick(&mut particles);
     }
+    recorder.finish(|| draw(&particles, zoom));
 
-    particles.len()
+    Ok(())
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let particles = parse(&input)?;
+pub fn solve(input: &str) -> Result<Answers> {
+    let particles = parse(input)?;
     let first = first(&particles);
     let second = second(particles);
 
-    println!("Day 20:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
@@ -137,4 +194,12 @@ mod tests {
         ).unwrap();
         assert_eq!(second(particles), 1);
     }
+
+    #[test]
+    fn test_animate() {
+        let mut recorder = Recorder::new(100);
+        animate(include_str!("../../data/d20-test"), &mut recorder).unwrap();
+        assert_eq!(recorder.len(), 11);
+        assert!(recorder.to_gif().is_ok());
+    }
 }
@@ -2,7 +2,11 @@ use failure::*;
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 
 use self::Pixel::{Off, On};
-use super::Result;
+use super::{Answers, Parameters, Result};
+use animation::Recorder;
+use bitgrid::BitGrid;
+use geom::Point;
+use image::{Image, Rgb};
 
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
 enum Pixel {
@@ -20,68 +24,53 @@ impl Pixel {
 
         Ok(result)
     }
+
+    fn is_on(c: char) -> Result<bool> {
+        Pixel::new(c).map(|pixel| pixel == On)
+    }
 }
 
 #[derive(Eq, PartialEq, Debug, Clone)]
 struct Pattern {
-    pixels: Vec<Vec<Pixel>>,
-    size: usize,
+    pixels: BitGrid,
 }
 
 impl Pattern {
-    fn new(pixels: Vec<Vec<Pixel>>) -> Pattern {
-        let size = pixels.len();
-        Pattern { pixels, size }
+    fn new(pixels: BitGrid) -> Pattern {
+        Pattern { pixels }
     }
 
     fn parse(s: &str) -> Result<Pattern> {
-        let size = s.lines().count();
-        let mut pixels = Vec::new();
-
-        for line in s.lines() {
-            let line: Vec<Pixel> = line.chars().map(Pixel::new).collect::<Result<_>>()?;
+        let pixels = BitGrid::from_chars(s, Pixel::is_on)?;
 
-            let len = line.len();
-            ensure!(
-                len == size,
-                "incorrect pattern size: left={}, right={}",
-                len,
-                size
-            );
-
-            pixels.push(line);
-        }
+        ensure!(
+            pixels.width() == pixels.height(),
+            "incorrect pattern size: left={}, right={}",
+            pixels.width(),
+            pixels.height()
+        );
 
         Ok(Pattern::new(pixels))
     }
 
-    fn split(&self) -> Vec<Pattern> {
-        let mut patterns = Vec::new();
-        let pix = &self.pixels;
+    fn size(&self) -> usize {
+        self.pixels.width()
+    }
 
-        let size = match self.size {
+    fn split(&self) -> Vec<Pattern> {
+        let size = match self.size() {
             x if x % 2 == 0 => 2,
             x if x % 3 == 0 => 3,
             x => panic!("unexpected size: {}", x),
         };
 
-        let n = self.size / size;
+        let n = self.size() / size;
+        let mut patterns = Vec::with_capacity(n * n);
 
         for y in 0..n {
-            let mut iters: Vec<_> = (0..size)
-                .map(|i| {
-                    let offset = i + (y * size);
-                    pix[offset].iter()
-                })
-                .collect();
-
-            for _ in 0..n {
-                let pat: Vec<Vec<Pixel>> = iters
-                    .iter_mut()
-                    .map(|it| it.take(size).cloned().collect())
-                    .collect();
-
-                patterns.push(Pattern::new(pat));
+            for x in 0..n {
+                let origin = Point::new((x * size) as i32, (y * size) as i32);
+                patterns.push(Pattern::new(self.pixels.subgrid(origin, size, size)));
             }
         }
 
@@ -94,86 +83,39 @@ impl Pattern {
             sq as usize
         };
 
-        let size = squares.first().unwrap().size;
-        let mut it = squares.iter();
-        let mut result = vec![Vec::with_capacity(n * size); n * size];
-
-        for y in 0..n {
-            let offset = y * size;
-            for _ in 0..n {
-                let pat = it.next().unwrap();
-                for (i, v) in pat.pixels.iter().enumerate() {
-                    result[offset + i].extend(v.iter())
-                }
-            }
+        let size = squares.first().unwrap().size();
+        let mut pixels = BitGrid::new(n * size, n * size);
+        for (i, square) in squares.iter().enumerate() {
+            let origin = Point::new((i % n * size) as i32, (i / n * size) as i32);
+            pixels.paste(origin, &square.pixels);
         }
 
-        Pattern::new(result)
-    }
-
-    fn rotate(&self) -> Result<Pattern> {
-        let pix = &self.pixels;
-        let n = self.size;
-        let rot = match n {
-            2 => vec![vec![pix[1][0], pix[0][0]], vec![pix[1][1], pix[0][1]]],
-            3 => vec![
-                vec![pix[2][0], pix[1][0], pix[0][0]],
-                vec![pix[2][1], pix[1][1], pix[0][1]],
-                vec![pix[2][2], pix[1][2], pix[0][2]],
-            ],
-            n => bail!("unexpected pattern size: {}", n),
-        };
-
-        Ok(Pattern::new(rot))
-    }
-
-    fn vflip(&self) -> Pattern {
-        let mut pix = self.pixels.clone();
-        let n = self.size;
-        pix.swap(0, n - 1);
-
-        Pattern::new(pix)
+        Pattern::new(pixels)
     }
 
-    fn hflip(&self) -> Pattern {
-        let mut pix = self.pixels.clone();
-        let n = self.size;
-        pix.iter_mut().for_each(|v| v.swap(0, n - 1));
-        Pattern::new(pix)
-    }
-
-    fn rotations(self) -> Result<Vec<Pattern>> {
-        let r1 = self.rotate()?;
-        let r2 = r1.rotate()?;
-        let r3 = r2.rotate()?;
+    fn rotations(self) -> Vec<Pattern> {
+        let r1 = self.pixels.rotate_right();
+        let r2 = r1.rotate_right();
+        let r3 = r2.rotate_right();
 
-        Ok(vec![self, r1, r2, r3])
+        vec![self, Pattern::new(r1), Pattern::new(r2), Pattern::new(r3)]
     }
 
-    fn permute(self) -> Result<Vec<Pattern>> {
+    fn permute(self) -> Vec<Pattern> {
         let mut rotations = Vec::new();
 
-        let hor = self.hflip();
-        let vert = self.vflip();
+        let hor = Pattern::new(self.pixels.flip_horizontal());
+        let vert = Pattern::new(self.pixels.flip_vertical());
 
-        rotations.extend(self.rotations()?);
-        rotations.extend(hor.rotations()?);
-        rotations.extend(vert.rotations()?);
+        rotations.extend(self.rotations());
+        rotations.extend(hor.rotations());
+        rotations.extend(vert.rotations());
 
-        Ok(rotations)
+        rotations
     }
 
     fn count_on(&self) -> usize {
-        let mut count = 0;
-        for line in &self.pixels {
-            for pix in line.iter() {
-                match pix {
-                    On => count += 1,
-                    Off => (),
-                }
-            }
-        }
-        count
+        self.pixels.count()
     }
 }
 
@@ -195,7 +137,7 @@ impl Rule {
             let s = it
                 .next()
                 .ok_or_else(|| err_msg("no source pattern present"))?;
-            Pattern::parse(&s)?.permute()?
+            Pattern::parse(&s)?.permute()
         };
 
         let out = {
@@ -249,8 +191,7 @@ pub struct Grid {
 
 impl Grid {
     fn new(rules: RuleSet) -> Grid {
-        let pixels = vec![vec![Off, On, Off], vec![Off, Off, On], vec![On, On, On]];
-        let pattern = Pattern::new(pixels);
+        let pattern = Pattern::parse(".#.\n..#\n###").unwrap();
         Grid { pattern, rules }
     }
 
@@ -272,6 +213,16 @@ impl Grid {
     fn count_on(&self) -> usize {
         self.pattern.count_on()
     }
+
+    /// The pixels that are on, centred on the origin.
+    fn draw(&self) -> Vec<(Point, Rgb)> {
+        let half = self.pattern.size() as i32 / 2;
+        self.pattern
+            .pixels
+            .ones()
+            .map(|p| (p - Point::new(half, half), Rgb::WHITE))
+            .collect()
+    }
 }
 
 pub fn evolve(mut grid: Grid, n: usize) -> usize {
@@ -279,12 +230,68 @@ pub fn evolve(mut grid: Grid, n: usize) -> usize {
     grid.count_on()
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let grid = Grid::from_str(&input)?;
-    let second = evolve(grid, 18);
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Params {
+    /// Number of enhancements in part 1
+    pub part1_iterations: usize,
+    /// Number of enhancements in part 2
+    pub part2_iterations: usize,
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params {
+            part1_iterations: 5,
+            part2_iterations: 18,
+        }
+    }
+}
+
+impl Parameters for Params {
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "part1_iterations" => self.part1_iterations = value.parse()?,
+            "part2_iterations" => self.part2_iterations = value.parse()?,
+            other => bail!("unknown parameter: {}", other),
+        }
+
+        Ok(())
+    }
+}
+
+pub fn solve(input: &str, params: &Params) -> Result<Answers> {
+    let grid = Grid::from_str(input)?;
+    let first = evolve(grid.clone(), params.part1_iterations);
+    let second = evolve(grid, params.part2_iterations);
+
+    Ok(Answers::new(first, second))
+}
+
+/// The art after as many enhancements as in part 1.
+pub fn render(input: &str, params: &Params) -> Result<Image> {
+    let mut grid = Grid::from_str(input)?;
+    (0..params.part1_iterations).for_each(|_| grid.enhance());
+
+    let image = Image::from_grid(&grid.pattern.pixels.to_grid(), |&on| {
+        if on {
+            Rgb::WHITE
+        } else {
+            Rgb::BLACK
+        }
+    });
+
+    Ok(image.scale(8))
+}
+
+/// The art growing over the enhancements of part 1.
+pub fn animate(input: &str, params: &Params, recorder: &mut Recorder) -> Result<()> {
+    let mut grid = Grid::from_str(input)?;
+    for _ in 0..params.part1_iterations {
+        recorder.step(|| grid.draw());
+        grid.enhance();
+    }
+    recorder.finish(|| grid.draw());
 
-    println!("Day 21:\nPart 2: {}\n", second);
     Ok(())
 }
 
@@ -292,10 +299,18 @@ pub fn solve() -> Result<()> {
 mod tests {
     use super::*;
 
+    fn pattern(rows: Vec<Vec<Pixel>>) -> Pattern {
+        let rows = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(|pixel| pixel == On))
+            .collect();
+        Pattern::new(BitGrid::from_rows(rows).unwrap())
+    }
+
     #[test]
     fn test_pattern() {
         let result = Pattern::parse(".#.\n..#\n###").unwrap();
-        let expected = Pattern::new(vec![
+        let expected = pattern(vec![
             vec![Off, On, Off],
             vec![Off, Off, On],
             vec![On, On, On],
@@ -306,8 +321,8 @@ mod tests {
     #[test]
     fn test_rotate() {
         let init = Pattern::parse(".#.\n..#\n###").unwrap();
-        let result = init.rotate().unwrap();
-        let expected = Pattern::new(vec![
+        let result = Pattern::new(init.pixels.rotate_right());
+        let expected = pattern(vec![
             vec![On, Off, Off],
             vec![On, Off, On],
             vec![On, On, Off],
@@ -321,10 +336,10 @@ mod tests {
         let init = Pattern::parse(input).unwrap();
         let result = init.split();
         let expected = vec![
-            Pattern::new(vec![vec![On, Off], vec![Off, Off]]),
-            Pattern::new(vec![vec![Off, On], vec![Off, Off]]),
-            Pattern::new(vec![vec![Off, Off], vec![On, Off]]),
-            Pattern::new(vec![vec![Off, Off], vec![Off, On]]),
+            pattern(vec![vec![On, Off], vec![Off, Off]]),
+            pattern(vec![vec![Off, On], vec![Off, Off]]),
+            pattern(vec![vec![Off, Off], vec![On, Off]]),
+            pattern(vec![vec![Off, Off], vec![Off, On]]),
         ];
         assert_eq!(result, expected);
     }
@@ -332,10 +347,10 @@ mod tests {
     #[test]
     fn test_join() {
         let input = vec![
-            Pattern::new(vec![vec![On, Off], vec![Off, Off]]),
-            Pattern::new(vec![vec![Off, On], vec![Off, Off]]),
-            Pattern::new(vec![vec![Off, Off], vec![On, Off]]),
-            Pattern::new(vec![vec![Off, Off], vec![Off, On]]),
+            pattern(vec![vec![On, Off], vec![Off, Off]]),
+            pattern(vec![vec![Off, On], vec![Off, Off]]),
+            pattern(vec![vec![Off, Off], vec![On, Off]]),
+            pattern(vec![vec![Off, Off], vec![Off, On]]),
         ];
         let result = Pattern::join(input.as_slice());
         let expected = Pattern::parse("#..#\n....\n....\n#..#").unwrap();
@@ -1,191 +1,185 @@
-use fnv::FnvHashMap;
-
-use self::Direction::{Down, Left, Right, Up};
-use self::State::{Clean, Flagged, Infected, Weakened};
-use super::Result;
-
-type Coord = (isize, isize);
+use super::{Answers, Parameters, Result};
+use animation::Recorder;
+use automaton::{Automaton, Rules};
+use geom::Direction::North;
+use geom::Point;
+use grid::Grid;
+use image::{Image, Rgb};
+use tui::{self, Simulation};
+
+/// The virus of part 1, which infects clean nodes and cleans infected ones.
+const INFECTION: &str = include_str!("../../data/d22-infection.rules");
+
+/// The evolved virus of part 2, which weakens and flags nodes in between.
+const EVOLVED_INFECTION: &str = include_str!("../../data/d22-evolved.rules");
+
+pub fn parse_grid(s: &str) -> Result<Grid<char>> {
+    Grid::from_chars(s, |c| match c {
+        '.' | '#' => Ok(c),
+        other => bail!("Unexpected state character: {}", other),
+    })
+}
 
-type Grid = FnvHashMap<Coord, State>;
+/// The virus carrier, starting in the middle of the grid and facing up.
+struct Carrier {
+    automaton: Automaton,
+    middle: Point,
+}
 
-pub fn parse_grid(s: &str) -> Result<FnvHashMap<Coord, State>> {
-    let mut grid = FnvHashMap::default();
+impl Carrier {
+    fn new(grid: &Grid<char>, rules: &str) -> Result<Carrier> {
+        let rules: Rules = rules.parse()?;
+        let mut automaton = Automaton::new(rules, grid)?;
+        let middle = Point::new(grid.width() as i32 / 2, grid.height() as i32 / 2);
+        automaton.add_agent(middle, North);
 
-    for (y, line) in s.lines().enumerate() {
-        let offset = (line.len() / 2) as isize;
-        for (x, c) in line.chars().enumerate() {
-            let x = x as isize - offset;
-            let y = y as isize - offset;
-            let p = (x, y);
-            let state = State::from_char(c)?;
-            grid.insert(p, state);
-        }
+        Ok(Carrier { automaton, middle })
     }
 
-    Ok(grid)
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Right,
-    Left,
-}
-
-impl Direction {
-    fn left(&self) -> Direction {
-        match *self {
-            Up => Left,
-            Down => Right,
-            Right => Up,
-            Left => Down,
-        }
+    fn burst(&mut self) {
+        self.automaton.step();
     }
 
-    fn right(&self) -> Direction {
-        match *self {
-            Up => Right,
-            Down => Left,
-            Right => Down,
-            Left => Up,
-        }
+    fn position(&self) -> Point {
+        self.automaton.agents()[0].position
     }
 
-    fn rev(&self) -> Direction {
-        self.right().right()
+    /// The nodes that aren't clean and the carrier itself, relative to the
+    /// middle of the original grid.
+    fn draw(&self) -> Vec<(Point, Rgb)> {
+        let middle = self.middle;
+        let mut pixels: Vec<(Point, Rgb)> = self
+            .automaton
+            .cells()
+            .map(|(p, state)| {
+                let colour = match state {
+                    'W' => Rgb::YELLOW,
+                    'F' => Rgb::BLUE,
+                    _ => Rgb::RED,
+                };
+                (p - middle, colour)
+            })
+            .collect();
+        pixels.push((self.position() - middle, Rgb::GREEN));
+
+        pixels
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-pub enum State {
-    Clean,
-    Weakened,
-    Infected,
-    Flagged,
+fn exec(grid: &Grid<char>, n: usize, rules: &str) -> Result<Carrier> {
+    let mut carrier = Carrier::new(grid, rules)?;
+    (0..n).for_each(|_| carrier.burst());
+    Ok(carrier)
 }
 
-impl State {
-    fn from_char(c: char) -> Result<State> {
-        let result = match c {
-            '.' => Clean,
-            '#' => Infected,
-            other => bail!("Unexpected state character: {}", other),
-        };
+pub fn infection(grid: Grid<char>, n: usize) -> Result<usize> {
+    Ok(exec(&grid, n, INFECTION)?.automaton.count())
+}
 
-        Ok(result)
-    }
+pub fn evolved_infection(grid: Grid<char>, n: usize) -> Result<usize> {
+    Ok(exec(&grid, n, EVOLVED_INFECTION)?.automaton.count())
+}
 
-    fn is_infected(&self) -> bool {
-        match self {
-            Infected => true,
-            _ => false,
-        }
-    }
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Params {
+    /// Number of bursts of activity in part 1
+    pub part1_bursts: usize,
+    /// Number of bursts of activity in part 2
+    pub part2_bursts: usize,
 }
 
-struct Carrier<F>
-where
-    F: Fn(State) -> State,
-{
-    pos: Coord,
-    dir: Direction,
-    grid: FnvHashMap<Coord, State>,
-    progressor: F,
-    count: usize,
-}
-
-impl<F> Carrier<F>
-where
-    F: Fn(State) -> State,
-{
-    fn new(grid: FnvHashMap<Coord, State>, progressor: F) -> Carrier<F> {
-        let pos = (0, 0);
-        let dir = Up;
-        let count = 0;
-
-        Carrier {
-            pos,
-            dir,
-            grid,
-            progressor,
-            count,
+impl Default for Params {
+    fn default() -> Params {
+        Params {
+            part1_bursts: 10_000,
+            part2_bursts: 10_000_000,
         }
     }
+}
 
-    fn forward(&mut self) {
-        let (x, y) = self.pos;
-
-        self.pos = match self.dir {
-            Up => (x, y - 1),
-            Down => (x, y + 1),
-            Right => (x + 1, y),
-            Left => (x - 1, y),
-        };
+impl Parameters for Params {
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "part1_bursts" => self.part1_bursts = value.parse()?,
+            "part2_bursts" => self.part2_bursts = value.parse()?,
+            other => bail!("unknown parameter: {}", other),
+        }
 
-        self.grid.entry(self.pos).or_insert(Clean);
+        Ok(())
     }
+}
 
-    fn update(&mut self) {
-        {
-            let state = self.grid.get_mut(&self.pos).unwrap();
-
-            match state {
-                Clean => self.dir = self.dir.left(),
-                Infected => self.dir = self.dir.right(),
-                Flagged => self.dir = self.dir.rev(),
-                Weakened => (),
-            }
+pub fn solve(input: &str, params: &Params) -> Result<Answers> {
+    let grid = parse_grid(input)?;
+    let first = infection(grid.clone(), params.part1_bursts)?;
+    let second = evolved_infection(grid, params.part2_bursts)?;
 
-            *state = (self.progressor)(*state);
+    Ok(Answers::new(first, second))
+}
 
-            if state.is_infected() {
-                self.count += 1;
-            }
-        }
+/// The nodes that aren't clean after the evolved virus is done with part 2,
+/// and the carrier in green.
+pub fn render(input: &str, params: &Params) -> Result<Image> {
+    let carrier = exec(&parse_grid(input)?, params.part2_bursts, EVOLVED_INFECTION)?;
+    Ok(Image::from_points(carrier.draw(), Rgb::BLACK).scale(2))
+}
 
-        self.forward();
+/// The virus of part 1 spreading, burst by burst.
+pub fn animate(input: &str, params: &Params, recorder: &mut Recorder) -> Result<()> {
+    let mut carrier = Carrier::new(&parse_grid(input)?, INFECTION)?;
+    for _ in 0..params.part1_bursts {
+        recorder.step(|| carrier.draw());
+        carrier.burst();
     }
+    recorder.finish(|| carrier.draw());
+
+    Ok(())
 }
 
-fn exec<F>(grid: Grid, n: usize, transition: F) -> usize
-where
-    F: Fn(State) -> State,
-{
-    let mut carrier = Carrier::new(grid, transition);
-    (0..n).for_each(|_| carrier.update());
-    carrier.count
+/// The carrier of part 1, for as many bursts as that part takes.
+struct Bursts {
+    carrier: Carrier,
+    left: usize,
 }
 
-pub fn infection(grid: Grid, n: usize) -> usize {
-    let evolve = |state| match state {
-        Clean => Infected,
-        Infected => Clean,
-        _ => panic!("unexpected state"),
-    };
+impl Simulation for Bursts {
+    fn step(&mut self) -> bool {
+        if self.left == 0 {
+            return false;
+        }
 
-    exec(grid, n, evolve)
-}
+        self.carrier.burst();
+        self.left -= 1;
+        true
+    }
 
-pub fn evolved_infection(grid: Grid, n: usize) -> usize {
-    let evolve = |state| match state {
-        Clean => Weakened,
-        Weakened => Infected,
-        Infected => Flagged,
-        Flagged => Clean,
-    };
+    fn cell(&self, p: Point) -> char {
+        let p = p + self.carrier.middle;
+        match self.carrier.automaton.agents()[0] {
+            agent if agent.position == p => tui::arrow(agent.direction),
+            _ => self.carrier.automaton.get(p),
+        }
+    }
 
-    exec(grid, n, evolve)
-}
+    fn focus(&self) -> Point {
+        self.carrier.position() - self.carrier.middle
+    }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let grid = parse_grid(&input)?;
-    let first = infection(grid.clone(), 10_000);
-    let second = evolved_infection(grid, 10_000_000);
+    fn status(&self) -> Vec<(&'static str, String)> {
+        let Point { x, y } = self.focus();
+        vec![
+            ("position", format!("{},{}", x, y)),
+            ("infections", self.carrier.automaton.count().to_string()),
+            ("bursts left", self.left.to_string()),
+        ]
+    }
+}
 
-    println!("Day 22:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+pub fn simulation(input: &str, params: &Params) -> Result<Box<Simulation>> {
+    Ok(Box::new(Bursts {
+        carrier: Carrier::new(&parse_grid(input)?, INFECTION)?,
+        left: params.part1_bursts,
+    }))
 }
 
 #[cfg(test)]
@@ -197,7 +191,7 @@ mod tests {
     #[test]
     fn test_first1() {
         let grid = parse_grid(IN).unwrap();
-        let result = infection(grid, 7);
+        let result = infection(grid, 7).unwrap();
         let expected = 5;
         assert_eq!(result, expected);
     }
@@ -205,7 +199,7 @@ mod tests {
     #[test]
     fn test_first2() {
         let grid = parse_grid(IN).unwrap();
-        let result = infection(grid, 70);
+        let result = infection(grid, 70).unwrap();
         let expected = 41;
         assert_eq!(result, expected);
     }
@@ -213,16 +207,34 @@ mod tests {
     #[test]
     fn test_first3() {
         let grid = parse_grid(IN).unwrap();
-        let result = infection(grid, 10_000);
+        let result = infection(grid, 10_000).unwrap();
         let expected = 5587;
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_solve_params() {
+        let params = Params {
+            part1_bursts: 70,
+            part2_bursts: 100,
+        };
+        let result = solve(IN, &params).unwrap();
+        let expected = Answers::new(41, 26);
+        assert_eq!(result, expected);
+    }
+
     #[test]
     fn test_second1() {
         let grid = parse_grid(IN).unwrap();
-        let result = evolved_infection(grid, 100);
+        let result = evolved_infection(grid, 100).unwrap();
         let expected = 26;
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_rules() {
+        for rules in &[INFECTION, EVOLVED_INFECTION] {
+            rules.parse::<Rules>().unwrap();
+        }
+    }
 }
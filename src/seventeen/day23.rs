@@ -1,89 +1,19 @@
-use failure::*;
+use rand::prng::XorShiftRng;
+use rand::Rng;
 
-use self::Inst::{Jnz, Mul, Set, Sub};
-use super::Result;
+use super::{Answers, Result};
+use variants::{Suite, Variant};
+use vm::Inst::{Mul, Set, Sub};
+use vm::{self, Extension, Inst, Machine, Operand, Reg};
 
-type Memory = Vec<i64>;
+/// The instructions of the coprocessor.
+const COPROCESSOR: &[&str] = &["set", "sub", "mul", "jnz"];
 
-#[derive(Debug, Clone)]
-struct Reg(u8);
-
-impl Reg {
-    fn parse(input: &str) -> Reg {
-        Reg(input.chars().next().expect("empty string") as u8)
-    }
-}
-
-#[derive(Debug, Clone)]
-enum RegVal {
-    Reg(u8),
-    Val(i64),
-}
-
-impl RegVal {
-    fn parse(input: &str) -> RegVal {
-        use self::RegVal::*;
-
-        if let Ok(v) = input.parse() {
-            Val(v)
-        } else {
-            let c = input.chars().next().expect("empty string");
-            Reg(c as u8)
-        }
-    }
-
-    fn eval(&self, mem: &[i64]) -> i64 {
-        use self::RegVal::*;
-
-        match *self {
-            Reg(reg) => mem[reg as usize],
-            Val(v) => v,
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-enum Inst {
-    Set(Reg, RegVal),
-    Sub(Reg, RegVal),
-    Mul(Reg, RegVal),
-    Jnz(RegVal, RegVal),
-}
-
-impl Inst {
-    fn parse(line: &str) -> Result<Inst> {
-        let mut it = line.split_whitespace();
-        let inst = it.next().ok_or_else(|| err_msg("no instruction"))?;
-        let result = match inst {
-            "jnz" => {
-                let cond = RegVal::parse(it.next().ok_or_else(|| err_msg("no register"))?);
-                let arg = RegVal::parse(it.next().ok_or_else(|| err_msg("no argument"))?);
-                Jnz(cond, arg)
-            }
-            inst => {
-                let reg = Reg::parse(it.next().ok_or_else(|| err_msg("no register"))?);
-                let arg = RegVal::parse(it.next().ok_or_else(|| err_msg("no argument"))?);
-                match inst {
-                    "set" => Set(reg, arg),
-                    "sub" => Sub(reg, arg),
-                    "mul" => Mul(reg, arg),
-                    _ => bail!("unkown instruction: {}", inst),
-                }
-            }
-        };
-
-        Ok(result)
-    }
-}
-
-fn parse_inst(input: &str) -> Result<Vec<Inst>> {
-    input
-        .trim()
-        .lines()
-        .map(Inst::parse)
-        .collect::<Result<Vec<Inst>>>()
+pub fn parse_inst(input: &str) -> Result<Vec<Inst>> {
+    vm::parse(input, COPROCESSOR)
 }
 
+/// How many times each instruction ran.
 #[derive(Debug, Clone)]
 struct Counter {
     set: u32,
@@ -101,92 +31,66 @@ impl Counter {
             jnz: 0,
         }
     }
+}
 
-    fn set(&mut self) {
-        self.set += 1;
-    }
-    fn sub(&mut self) {
-        self.sub += 1;
+impl Extension for Counter {
+    fn before(&mut self, inst: &Inst) {
+        match *inst {
+            Set(..) => self.set += 1,
+            Sub(..) => self.sub += 1,
+            Mul(..) => self.mul += 1,
+            _ => self.jnz += 1,
+        }
     }
+}
 
-    fn mul(&mut self) {
-        self.mul += 1;
-    }
+pub fn debug_processor(input: &str) -> Result<u32> {
+    let inst = parse_inst(input)?;
+    let mut program = Machine::new(&inst, Counter::new());
+    program.run();
 
-    fn jnz(&mut self) {
-        self.jnz += 1;
-    }
+    Ok(program.ext.mul)
 }
 
-#[derive(Clone)]
-struct Program {
-    mem: Memory,
-    inst: Vec<Inst>,
-    ip: usize,
-    count: Counter,
-}
+/// Runs the program with the debug flag cleared, like the real processor.
+pub fn run_processor(input: &str) -> Result<i64> {
+    let inst = parse_inst(input)?;
+    let mut program = Machine::new(&inst, ());
+    program.regs['a'] = 1;
+    program.run();
 
-impl Program {
-    fn from_inst(inst: Vec<Inst>) -> Program {
-        let mem = vec![0; 256];
-        Program {
-            mem,
-            inst,
-            ip: 0,
-            count: Counter::new(),
-        }
-    }
+    Ok(program.regs['h'])
+}
 
-    pub fn exec(&mut self) {
-        while let Some(it) = self.inst.get(self.ip) {
-            match *it {
-                Set(Reg(reg), ref arg) => {
-                    self.count.set();
-                    self.mem[reg as usize] = arg.eval(&self.mem);
-                }
-                Sub(Reg(reg), ref arg) => {
-                    self.count.sub();
-                    self.mem[reg as usize] -= arg.eval(&self.mem);
-                }
-                Mul(Reg(reg), ref arg) => {
-                    self.count.mul();
-                    self.mem[reg as usize] *= arg.eval(&self.mem);
-                }
-                Jnz(ref cond, ref offset) => {
-                    self.count.jnz();
-                    let cond = cond.eval(&self.mem);
-                    if cond != 0 {
-                        let o = offset.eval(&self.mem);
-
-                        if o < 0 {
-                            self.ip = self.ip.checked_sub(-o as usize).expect("underflow");
-                        } else {
-                            self.ip = self.ip.checked_add(o as usize).expect("overflow");
-                        }
-                        continue;
-                    }
-                }
-            }
-            self.ip += 1;
+/// The literal argument of the instruction at `i`, which must act on `reg`.
+fn literal(inst: &[Inst], i: usize, reg: char) -> Result<i64> {
+    match inst.get(i) {
+        Some(&Set(Reg(r), Operand::Val(v)))
+        | Some(&Sub(Reg(r), Operand::Val(v)))
+        | Some(&Mul(Reg(r), Operand::Val(v)))
+            if r == reg as u8 =>
+        {
+            Ok(v)
         }
+        _ => bail!("unexpected instruction {}: {:?}", i + 1, inst.get(i)),
     }
 }
 
-pub fn debug_processor(input: &str) -> Result<u32> {
+/// Counts the composite numbers the program tests, instead of testing them
+/// the slow way.
+pub fn optimize_processor(input: &str) -> Result<u64> {
     let inst = parse_inst(input)?;
-    let mut program = Program::from_inst(inst);
-    program.exec();
+    let b = literal(&inst, 0, 'b')? * literal(&inst, 4, 'b')? - literal(&inst, 5, 'b')?;
+    let c = b - literal(&inst, 7, 'c')?;
+    let step = -literal(&inst, 30, 'b')?;
 
-    Ok(program.count.mul)
-}
+    ensure!(b > 2, "the first number must be larger than 2, got {}", b);
+    ensure!(c >= b, "the range {}..{} is empty", b, c);
+    ensure!(step > 0, "the step must be positive, got {}", step);
+    ensure!((c - b) % step == 0, "{} is never reached from {}", c, b);
 
-pub fn optimize_processor(input: &str) -> Result<u64> {
-    let mut b = input.split_whitespace().nth(2).unwrap().parse()?;
-    b = b * 100 + 100_000;
     let mut h = 0;
-    let end = b + 17_000 + 1;
-
-    for x in (b..end).step_by(17) {
+    for x in (b..c + 1).step_by(step as usize) {
         for i in 2..x {
             if x % i == 0 {
                 h += 1;
@@ -198,13 +102,55 @@ pub fn optimize_processor(input: &str) -> Result<u64> {
     Ok(h)
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let first = debug_processor(&input)?;
-    let second = optimize_processor(&input)?;
+const TEMPLATE: &str = include_str!("../../data/d23-test");
+
+/// The real program with small enough numbers that it can be run as is.
+fn generate(rng: &mut XorShiftRng) -> String {
+    let x = rng.gen_range(3, 20);
+    let m = rng.gen_range(1, 5);
+    let n = -rng.gen_range(0, 50);
+    let s = rng.gen_range(1, 20);
+    let r = -s * rng.gen_range(0, 4);
+
+    TEMPLATE
+        .lines()
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => format!("set b {}", x),
+            4 => format!("mul b {}", m),
+            5 => format!("sub b {}", n),
+            7 => format!("sub c {}", r),
+            30 => format!("sub b {}", -s),
+            _ => line.to_owned(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn variants() -> Suite {
+    Suite {
+        variants: vec![
+            Variant {
+                part: 2,
+                name: "optimized",
+                solve: |input| Ok(optimize_processor(input)?.into()),
+            },
+            Variant {
+                part: 2,
+                name: "run",
+                solve: |input| Ok(run_processor(input)?.into()),
+            },
+        ],
+        examples: vec![],
+        generate: Some(generate),
+    }
+}
+
+pub fn solve(input: &str) -> Result<Answers> {
+    let first = debug_processor(input)?;
+    let second = optimize_processor(input)?;
 
-    println!("Day 23:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
 }
 
 #[allow(dead_code)]
@@ -224,4 +170,10 @@ mod tests {
     fn test_p2() {
         check(optimize_processor(FULL), 907)
     }
+
+    #[test]
+    fn test_variants() {
+        let report = ::variants::compare(&variants(), 10, 23);
+        assert!(report.divergences.is_empty(), "{}", report);
+    }
 }
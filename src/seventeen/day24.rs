@@ -1,27 +1,22 @@
-use failure::*;
+use std::collections::BTreeSet;
 
-use super::Result;
+use bit_vec::BitVec;
+use rand::prng::XorShiftRng;
+use rand::Rng;
+
+use super::{Answers, Result};
+use parsers::{self, integer, Input};
+use search;
 
 type Connector = (u32, u32);
 
+named!(
+    connector(Input) -> Connector,
+    separated_pair!(integer, char!('/'), integer)
+);
+
 pub fn parse_connectors(s: &str) -> Result<Vec<Connector>> {
-    s.trim()
-        .lines()
-        .map(|s| {
-            let mut it = s.split('/');
-            let first = it
-                .next()
-                .ok_or_else(|| err_msg("unexpected end of input"))?
-                .parse()?;
-
-            let second = it
-                .next()
-                .ok_or_else(|| err_msg("unexpected end of input"))?
-                .parse()?;
-
-            Ok((first, second))
-        })
-        .collect()
+    parsers::lines(s, connector)
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
@@ -31,74 +26,109 @@ struct Bridge {
     pins: u32,
 }
 
-impl Bridge {
-    fn new() -> Bridge {
-        Bridge {
-            length: 0,
-            strength: 0,
-            pins: 0,
-        }
-    }
+/// A bridge as it is being built: the connectors it uses so far, and the
+/// free port at its end.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Partial {
+    used: BitVec,
+    pins: u32,
+    strength: u32,
+}
 
-    fn extend(&self, (pin, pout): Connector) -> Option<Bridge> {
-        let pins = match self.pins {
-            p if p == pin => pout,
-            p if p == pout => pin,
-            _ => return None,
-        };
-
-        Some(Bridge {
-            length: self.length + 1,
-            strength: self.strength + pin + pout,
-            pins,
+/// Every bridge that can be built from `xs`, found as the states reachable
+/// from the empty bridge by adding one connector at a time.
+fn bridges(xs: &[Connector]) -> Vec<Bridge> {
+    let start = Partial {
+        used: BitVec::from_elem(xs.len(), false),
+        pins: 0,
+        strength: 0,
+    };
+
+    let extend = |partial: &Partial| {
+        let mut next = Vec::new();
+        for (i, &(a, b)) in xs.iter().enumerate() {
+            let pins = match partial.pins {
+                _ if partial.used[i] => continue,
+                p if p == a => b,
+                p if p == b => a,
+                _ => continue,
+            };
+
+            let mut used = partial.used.clone();
+            used.set(i, true);
+            next.push(Partial {
+                used,
+                pins,
+                strength: partial.strength + a + b,
+            });
+        }
+        next
+    };
+
+    search::reachable(start, extend)
+        .into_iter()
+        .map(|(partial, length)| Bridge {
+            length: length as u32,
+            strength: partial.strength,
+            pins: partial.pins,
         })
-    }
+        .collect()
 }
 
-fn optimal_bridge<F>(quality: F, xs: &mut [Connector]) -> Bridge
-where
-    F: Fn(Bridge, Bridge) -> Bridge,
-{
-    fn backtrack<G>(quality: &G, xs: &mut [Connector], i: usize, mut max: Bridge) -> Bridge
-    where
-        G: Fn(Bridge, Bridge) -> Bridge,
-    {
-        let bridge = max;
-        for j in i..xs.len() {
-            xs.swap(i, j);
-
-            if let Some(bridge) = bridge.extend(xs[i]) {
-                max = quality(max, backtrack(quality, xs, i + 1, bridge));
-            }
-
-            xs.swap(j, i);
-        }
+fn strongest(bridges: &[Bridge]) -> u32 {
+    bridges.iter().map(|b| b.strength).max().unwrap_or(0)
+}
 
-        max
-    }
+/// The strength of the longest bridge, the strongest one among equals.
+fn longest(bridges: &[Bridge]) -> u32 {
+    bridges.iter().max().map_or(0, |b| b.strength)
+}
 
-    backtrack(&quality, xs, 0, Bridge::new())
+pub fn strongest_bridge(connectors: &[Connector]) -> u32 {
+    strongest(&bridges(connectors))
 }
 
-pub fn strongest_bridge(connectors: &mut [Connector]) -> u32 {
-    let stronger = |b1: Bridge, b2: Bridge| if b1.strength > b2.strength { b1 } else { b2 };
-    let bridge = optimal_bridge(stronger, connectors);
-    bridge.strength
+pub fn longest_bridge(connectors: &[Connector]) -> u32 {
+    longest(&bridges(connectors))
 }
 
-pub fn longest_bridge(connectors: &mut [Connector]) -> u32 {
-    let bridge = optimal_bridge(Bridge::max, connectors);
-    bridge.strength
+pub fn solve(input: &str) -> Result<Answers> {
+    let bridges = bridges(&parse_connectors(input)?);
+    let first = strongest(&bridges);
+    let second = longest(&bridges);
+
+    Ok(Answers::new(first, second))
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let mut connectors = parse_connectors(&input)?;
-    let first = strongest_bridge(&mut connectors);
-    let second = longest_bridge(&mut connectors);
+/// `size` distinct components, at least one of which fits the zero pin port.
+pub fn generate(rng: &mut XorShiftRng, size: usize) -> String {
+    let size = size.max(1);
+    let mut max_port = 50;
+    while (max_port + 1) * (max_port + 2) / 2 < size {
+        max_port += 1;
+    }
 
-    println!("Day 24:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    let mut connectors = BTreeSet::new();
+    connectors.insert((0, rng.gen_range(0, max_port + 1)));
+    while connectors.len() < size {
+        let a = rng.gen_range(0, max_port + 1);
+        let b = rng.gen_range(0, max_port + 1);
+        connectors.insert((a.min(b), a.max(b)));
+    }
+
+    let mut lines: Vec<String> = connectors
+        .into_iter()
+        .map(|(a, b)| {
+            if rng.gen() {
+                format!("{}/{}", a, b)
+            } else {
+                format!("{}/{}", b, a)
+            }
+        })
+        .collect();
+
+    rng.shuffle(&mut lines);
+    lines.join("\n")
 }
 
 #[allow(dead_code)]
@@ -110,13 +140,73 @@ mod tests {
 
     #[test]
     fn test_first() {
-        let mut connectors = parse_connectors(IN).unwrap();
-        assert_eq!(strongest_bridge(&mut connectors), 31);
+        let connectors = parse_connectors(IN).unwrap();
+        assert_eq!(strongest_bridge(&connectors), 31);
     }
 
     #[test]
     fn test_second() {
-        let mut connectors = parse_connectors(IN).unwrap();
-        assert_eq!(longest_bridge(&mut connectors), 19);
+        let connectors = parse_connectors(IN).unwrap();
+        assert_eq!(longest_bridge(&connectors), 19);
+    }
+
+    /// The length and strength of every bridge, by trying each unused
+    /// connector at every step.
+    fn all_bridges(
+        connectors: &[Connector],
+        used: &mut Vec<bool>,
+        pins: u32,
+        (length, strength): (u32, u32),
+        bridges: &mut Vec<(u32, u32)>,
+    ) {
+        bridges.push((length, strength));
+        for (i, &(a, b)) in connectors.iter().enumerate() {
+            let next = match pins {
+                _ if used[i] => continue,
+                p if p == a => b,
+                p if p == b => a,
+                _ => continue,
+            };
+
+            used[i] = true;
+            all_bridges(
+                connectors,
+                used,
+                next,
+                (length + 1, strength + a + b),
+                bridges,
+            );
+            used[i] = false;
+        }
+    }
+
+    /// The strongest and the longest bridge, by brute force.
+    fn reference(connectors: &[Connector]) -> (u32, u32) {
+        let mut bridges = Vec::new();
+        let mut used = vec![false; connectors.len()];
+        all_bridges(connectors, &mut used, 0, (0, 0), &mut bridges);
+        let strongest = bridges.iter().map(|&(_, s)| s).max().unwrap();
+        let (_, longest) = bridges.iter().cloned().max().unwrap();
+        (strongest, longest)
+    }
+
+    #[test]
+    fn test_generated() {
+        for seed in 0..20 {
+            let mut rng = ::seeded_rng(seed);
+            let generated = parse_connectors(&generate(&mut rng, 20)).unwrap();
+            assert_eq!(generated.len(), 20);
+
+            // Few ports, so that bridges branch a lot
+            let dense: Vec<Connector> = (0..12)
+                .map(|_| (rng.gen_range(0, 7), rng.gen_range(0, 7)))
+                .collect();
+
+            for connectors in &[&generated, &dense] {
+                let expected = reference(connectors);
+                let actual = (strongest_bridge(connectors), longest_bridge(connectors));
+                assert_eq!(actual, expected, "seed {}: {:?}", seed, connectors);
+            }
+        }
     }
 }
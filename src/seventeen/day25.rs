@@ -2,7 +2,11 @@ use fnv::FnvHashMap;
 use std::collections::VecDeque;
 
 use self::Direction::{Left, Right};
-use super::Result;
+use super::{Answers, Result};
+use animation::Recorder;
+use geom::Point;
+use image::Rgb;
+use tui::Simulation;
 
 const FILTER: [char; 6] = ['.', '-', ':', ' ', '\t', '\n'];
 
@@ -156,17 +160,97 @@ impl Program {
     }
 }
 
+/// The tape around every position the cursor has been, with the cursor in
+/// red, as a single row relative to where it started.
+fn draw(prog: &Program, seen: (usize, usize)) -> Vec<(Point, Rgb)> {
+    let x = |i: usize| i as i32 - prog.steps as i32;
+    let mut pixels: Vec<(Point, Rgb)> = (seen.0..=seen.1)
+        .filter(|&i| prog.tape[i] == 1)
+        .map(|i| (Point::new(x(i), 0), Rgb::WHITE))
+        .collect();
+    pixels.push((Point::new(x(prog.cursor), 0), Rgb::RED));
+
+    pixels
+}
+
+/// The Turing machine writing its tape, step by step.
+pub fn animate(input: &str, recorder: &mut Recorder) -> Result<()> {
+    let mut prog = Program::from_str(input)?;
+    let mut seen = (prog.cursor, prog.cursor);
+    for _ in 0..prog.steps {
+        recorder.step(|| draw(&prog, seen));
+        prog.step();
+        seen = (seen.0.min(prog.cursor), seen.1.max(prog.cursor));
+    }
+    recorder.finish(|| draw(&prog, seen));
+
+    Ok(())
+}
+
+/// The machine running for its number of steps, keeping the checksum as it
+/// goes.
+struct Machine {
+    prog: Program,
+    done: usize,
+    ones: usize,
+}
+
+impl Simulation for Machine {
+    fn step(&mut self) -> bool {
+        if self.done == self.prog.steps {
+            return false;
+        }
+
+        let before = self.prog.tape[self.prog.cursor];
+        let Actions(after, _, _) = self.prog.inst[&(self.prog.state, before)];
+        self.prog.step();
+        self.ones = self.ones + usize::from(after) - usize::from(before);
+        self.done += 1;
+        true
+    }
+
+    fn cell(&self, p: Point) -> char {
+        let i = p.x + self.prog.steps as i32;
+        match p.y {
+            0 if i >= 0 && (i as usize) < self.prog.tape.len() => {
+                (b'0' + self.prog.tape[i as usize]) as char
+            }
+            1 if i as usize == self.prog.cursor => '^',
+            _ => ' ',
+        }
+    }
+
+    fn focus(&self) -> Point {
+        Point::new(self.prog.cursor as i32 - self.prog.steps as i32, 0)
+    }
+
+    fn status(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("state", self.prog.state.0.to_string()),
+            ("cursor", self.focus().x.to_string()),
+            ("checksum", self.ones.to_string()),
+            ("steps left", (self.prog.steps - self.done).to_string()),
+        ]
+    }
+}
+
+pub fn simulation(input: &str) -> Result<Box<Simulation>> {
+    Ok(Box::new(Machine {
+        prog: Program::from_str(input)?,
+        done: 0,
+        ones: 0,
+    }))
+}
+
 pub fn first(input: &str) -> Result<usize> {
     let mut prog = Program::from_str(input)?;
     Ok(prog.eval())
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let first = first(&input)?;
+pub fn solve(input: &str) -> Result<Answers> {
+    let first = first(input)?;
 
-    println!("Day 25:\nPart 1: {}\n", first);
-    Ok(())
+    Ok(Answers::part1(first))
 }
 
 #[allow(dead_code)]
@@ -1,47 +1,10 @@
-use fnv::FnvHashMap;
-
-use self::Direction::*;
-use super::Result;
-
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-
-impl Point {
-    fn new() -> Point {
-        Point { x: 0, y: 0 }
-    }
-
-    fn distance(&self, p: Point) -> u32 {
-        ((self.x - p.x).abs() + (self.y - p.y).abs()) as u32
-    }
-
-    fn distance_from_start(&self) -> u32 {
-        self.distance(Point::new())
-    }
+use std::iter;
 
-    fn neighbours(&self) -> Vec<Point> {
-        let Point { x, y } = *self;
-        let mut result = Vec::with_capacity(9);
-        for x in x - 1..=x + 1 {
-            for y in y - 1..=y + 1 {
-                result.push(Point { x, y });
-            }
-        }
+use fnv::FnvHashMap;
 
-        result
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
+use super::{Answers, Parameters, Result};
+use geom::Direction::South;
+use geom::{Direction, Point};
 
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
 struct Position {
@@ -52,31 +15,17 @@ struct Position {
 impl Position {
     fn new() -> Position {
         Position {
-            d: Direction::South,
-            p: Point::new(),
+            d: South,
+            p: Point::origin(),
         }
     }
 
     fn turn(&mut self) {
-        self.d = match self.d {
-            North => West,
-            South => East,
-            East => North,
-            West => South,
-        };
+        self.d = self.d.left();
     }
 
     fn forward(&mut self) {
-        let Point { x, y } = self.p;
-
-        let (x, y) = match self.d {
-            North => (x, y + 1),
-            South => (x, y - 1),
-            East => (x + 1, y),
-            West => (x - 1, y),
-        };
-
-        self.p = Point { x, y };
+        self.p = self.p.step(self.d);
     }
 }
 
@@ -107,7 +56,7 @@ impl<'a> Iterator for Spiral {
 
         if count == 0 {
             self.count = 1;
-            return Some(Point::new());
+            return Some(Point::origin());
         }
 
         if dist == 0 {
@@ -133,7 +82,7 @@ impl SumSpiral {
     fn new() -> SumSpiral {
         let sp = Spiral::new();
         let mut seen = FnvHashMap::default();
-        seen.insert(Point::new(), 1);
+        seen.insert(Point::origin(), 1);
 
         SumSpiral { sp, seen }
     }
@@ -144,15 +93,21 @@ impl Iterator for SumSpiral {
 
     fn next(&mut self) -> Option<u32> {
         let p = self.sp.next().unwrap();
-        let val: u32 = p.neighbours().iter().filter_map(|q| self.seen.get(q)).sum();
+        // Only the first square has a value of its own yet
+        let val: u32 = iter::once(p)
+            .chain(p.neighbours8())
+            .filter_map(|q| self.seen.get(&q))
+            .sum();
 
         self.seen.insert(p, val);
         Some(val)
     }
 }
 
-pub fn nthspiral(n: usize) -> u32 {
-    Spiral::new().nth(n - 1).unwrap().distance_from_start()
+pub fn nthspiral(n: usize) -> Result<u32> {
+    ensure!(n >= 1, "squares are numbered from 1, not {}", n);
+    let p = Spiral::new().nth(n - 1).unwrap();
+    Ok(p.manhattan(Point::origin()) as u32)
 }
 
 pub fn firstlarger(n: u32) -> u32 {
@@ -166,12 +121,33 @@ pub fn firstlarger(n: u32) -> u32 {
     result
 }
 
-pub fn solve() -> Result<()> {
-    let first = nthspiral(265_149);
-    let second = firstlarger(265_149);
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Params {
+    /// The square to look up, instead of the one given as puzzle input
+    pub target: Option<u32>,
+}
+
+impl Parameters for Params {
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "target" => self.target = Some(value.parse()?),
+            other => bail!("unknown parameter: {}", other),
+        }
 
-    println!("Day 3:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+        Ok(())
+    }
+}
+
+pub fn solve(input: &str, params: &Params) -> Result<Answers> {
+    let target = match params.target {
+        Some(target) => target,
+        None => input.trim().parse()?,
+    };
+
+    let first = nthspiral(target as usize)?;
+    let second = firstlarger(target);
+
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
@@ -180,42 +156,47 @@ mod tests {
 
     #[test]
     fn test_nthspiral1() {
-        assert_eq!(nthspiral(1), 0);
+        assert_eq!(nthspiral(1).unwrap(), 0);
     }
 
     #[test]
     fn test_nthspiral2() {
-        assert_eq!(nthspiral(2), 1);
+        assert_eq!(nthspiral(2).unwrap(), 1);
     }
 
     #[test]
     fn test_nthspiral4() {
-        assert_eq!(nthspiral(4), 1);
+        assert_eq!(nthspiral(4).unwrap(), 1);
     }
 
     #[test]
     fn test_nthspiral6() {
-        assert_eq!(nthspiral(6), 1);
+        assert_eq!(nthspiral(6).unwrap(), 1);
     }
 
     #[test]
     fn test_nthspiral8() {
-        assert_eq!(nthspiral(8), 1);
+        assert_eq!(nthspiral(8).unwrap(), 1);
     }
 
     #[test]
     fn test_nthspiral12() {
-        assert_eq!(nthspiral(12), 3);
+        assert_eq!(nthspiral(12).unwrap(), 3);
     }
 
     #[test]
     fn test_nthspiral23() {
-        assert_eq!(nthspiral(23), 2);
+        assert_eq!(nthspiral(23).unwrap(), 2);
     }
 
     #[test]
     fn test_nthspiral1024() {
-        assert_eq!(nthspiral(1024), 31);
+        assert_eq!(nthspiral(1024).unwrap(), 31);
+    }
+
+    #[test]
+    fn test_nthspiral0() {
+        assert!(nthspiral(0).is_err());
     }
 
     #[test]
@@ -1,6 +1,6 @@
 use fnv::FnvHashSet;
 
-use super::Result;
+use super::{Answers, Result};
 
 pub fn check_password(input: &str) -> u32 {
     input
@@ -29,13 +29,11 @@ pub fn check_anagram(input: &str) -> u32 {
         .count() as u32
 }
 
-pub fn solve() -> Result<()> {
-    let passphrases = super::get_input()?;
-    let first = check_password(&passphrases);
-    let second = check_anagram(&passphrases);
+pub fn solve(passphrases: &str) -> Result<Answers> {
+    let first = check_password(passphrases);
+    let second = check_anagram(passphrases);
 
-    println!("Day 4:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
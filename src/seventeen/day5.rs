@@ -1,4 +1,4 @@
-use super::Result;
+use super::{Answers, Result};
 
 pub fn parse_buffer(s: &str) -> Result<Vec<i32>> {
     s.trim()
@@ -35,14 +35,12 @@ pub fn buffer_jump_extreme(lines: &mut [i32]) -> u32 {
     j
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let mut buffer = parse_buffer(&input)?;
+pub fn solve(input: &str) -> Result<Answers> {
+    let mut buffer = parse_buffer(input)?;
     let first = buffer_jump(&mut buffer.clone());
     let second = buffer_jump_extreme(&mut buffer);
 
-    println!("Day 5:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
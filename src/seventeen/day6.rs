@@ -1,47 +1,44 @@
-use fnv::FnvHashMap;
-
-use super::Result;
+use super::{Answers, Result};
+use cycle;
+use parsers::{self, integers};
 
 pub fn parse_memory(s: &str) -> Result<Vec<u32>> {
-    s.trim()
-        .split_whitespace()
-        .map(|s| s.parse().map_err(Into::into))
-        .collect::<Result<_>>()
+    parsers::parse(s.trim(), integers)
 }
 
-pub fn redistribute(input: &mut [u32]) -> (u32, u32) {
-    let mut seen: FnvHashMap<Vec<u32>, u32> = FnvHashMap::default();
-    let n = input.len();
+/// Moves the blocks of the fullest bank to the ones after it, one by one.
+fn reallocate(banks: &[u32]) -> Vec<u32> {
+    let mut banks = banks.to_vec();
+    let n = banks.len();
 
-    for i in 0.. {
-        if let Some(x) = seen.insert(input.to_vec(), i) {
-            return (i, i - x);
-        }
+    let (mut key, &el) = banks
+        .iter()
+        .enumerate()
+        .max_by_key(|&(i, v)| (v, -(i as i32)))
+        .unwrap();
 
-        let (mut key, &el) = input
-            .iter()
-            .enumerate()
-            .max_by_key(|&(i, v)| (v, -(i as i32)))
-            .unwrap();
+    banks[key] = 0;
+    key += 1;
 
-        input[key] = 0;
+    for _ in 0..el {
+        banks[key % n] += 1;
         key += 1;
-
-        for _ in 0..el {
-            input[key % n] += 1;
-            key += 1;
-        }
     }
-    unreachable!()
+
+    banks
+}
+
+pub fn redistribute(input: &[u32]) -> (u32, u32) {
+    let cycle = cycle::brent(input.to_vec(), |banks| reallocate(banks));
+
+    ((cycle.start + cycle.length) as u32, cycle.length as u32)
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let mut memory = parse_memory(&input)?;
-    let (first, second) = redistribute(&mut memory);
+pub fn solve(input: &str) -> Result<Answers> {
+    let memory = parse_memory(input)?;
+    let (first, second) = redistribute(&memory);
 
-    println!("Day 6:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
@@ -50,7 +47,7 @@ mod tests {
 
     #[test]
     fn test_both() {
-        let mut input = [0, 2, 7, 0];
-        assert_eq!(redistribute(&mut input), (5, 4));
+        let input = [0, 2, 7, 0];
+        assert_eq!(redistribute(&input), (5, 4));
     }
 }
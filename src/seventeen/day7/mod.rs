@@ -1,10 +1,12 @@
 mod parsing;
 
 use failure::err_msg;
-use fnv::FnvHashMap as HashMap;
+use fnv::{FnvHashMap as HashMap, FnvHashSet as HashSet};
+use rand::prng::XorShiftRng;
+use rand::Rng;
 
-use self::parsing::parse_line;
-use super::Result;
+use self::parsing::parse_lines;
+use super::{Answers, Result};
 
 type Name<'a> = &'a str;
 type Attributes<'a> = (u32, Vec<&'a str>);
@@ -16,15 +18,10 @@ pub struct Tree<'a> {
 
 impl<'a> Tree<'a> {
     pub fn from_str(s: &'a str) -> Result<Self> {
-        let tree: HashMap<Name, Attributes> = s
-            .trim()
-            .lines()
-            .map(|l| {
-                parse_line(l)
-                    .map(|(n, w, c)| (n, (w, c)))
-                    .map_err(|_| err_msg("failed to parse tree"))
-            })
-            .collect::<Result<_>>()?;
+        let tree: HashMap<Name, Attributes> = parse_lines(s)?
+            .into_iter()
+            .map(|(n, w, c)| (n, (w, c)))
+            .collect();
 
         ensure!(
             tree.iter()
@@ -49,8 +46,7 @@ impl<'a> Tree<'a> {
     fn fix_tree(&self, offset: Option<i32>, root: &str) -> Option<u32> {
         let (w, ref children) = self.tree[root];
         let weights: Vec<u32> = children.iter().map(|c| self.tree_weight(c)).collect();
-        let first = weights.first().unwrap();
-        let balanced = weights.iter().all(|w| w == first);
+        let balanced = weights.windows(2).all(|w| w[0] == w[1]);
 
         if balanced {
             offset.map(|v| (w as i32 + v) as u32)
@@ -77,14 +73,110 @@ impl<'a> Tree<'a> {
     }
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let tree = Tree::from_str(&input)?;
+pub fn solve(input: &str) -> Result<Answers> {
+    let tree = Tree::from_str(input)?;
     let first = tree.root;
     let second = tree.solve();
 
-    println!("Day 7:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
+}
+
+struct Program {
+    name: String,
+    weight: u32,
+    children: Vec<usize>,
+}
+
+/// Builds a balanced tower of about `budget` programs, returning the index of
+/// its root and its total weight.
+fn build_tower(
+    rng: &mut XorShiftRng,
+    budget: usize,
+    names: &mut HashSet<String>,
+    programs: &mut Vec<Program>,
+) -> (usize, u32) {
+    let name = loop {
+        let len = rng.gen_range(4, 8);
+        let name: String = (0..len)
+            .map(|_| rng.gen_range(b'a', b'z' + 1) as char)
+            .collect();
+        if names.insert(name.clone()) {
+            break name;
+        }
+    };
+
+    let mut children = Vec::new();
+    let mut totals = Vec::new();
+    if budget >= 4 {
+        let count = rng.gen_range(3, 6).min(budget - 1);
+        for i in 0..count {
+            let share = (budget - 1) / count + if i < (budget - 1) % count { 1 } else { 0 };
+            let (child, total) = build_tower(rng, share, names, programs);
+            children.push(child);
+            totals.push(total);
+        }
+    }
+
+    // Make the lighter children as heavy as the heaviest one
+    let heaviest = totals.iter().cloned().max().unwrap_or(0);
+    for (&child, &total) in children.iter().zip(&totals) {
+        programs[child].weight += heaviest - total;
+    }
+
+    let weight = rng.gen_range(1, 100);
+    programs.push(Program {
+        name,
+        weight,
+        children,
+    });
+
+    (programs.len() - 1, weight + heaviest * totals.len() as u32)
+}
+
+/// A tower of about `size` programs in which exactly one program has the
+/// wrong weight.
+pub fn generate(rng: &mut XorShiftRng, size: usize) -> String {
+    unbalanced_tower(rng, size).0
+}
+
+/// The tower of `generate`, and the weight that balances it.
+fn unbalanced_tower(rng: &mut XorShiftRng, size: usize) -> (String, u32) {
+    let mut programs = Vec::new();
+    let (root, _) = build_tower(rng, size.max(4), &mut HashSet::default(), &mut programs);
+
+    let wrong = loop {
+        let i = rng.gen_range(0, programs.len());
+        if i != root {
+            break i;
+        }
+    };
+    let balanced = programs[wrong].weight;
+    let delta = rng.gen_range(1, 20);
+    if programs[wrong].weight > delta && rng.gen() {
+        programs[wrong].weight -= delta;
+    } else {
+        programs[wrong].weight += delta;
+    }
+
+    let mut lines: Vec<String> = programs
+        .iter()
+        .map(|p| {
+            let mut line = format!("{} ({})", p.name, p.weight);
+            if !p.children.is_empty() {
+                let children: Vec<&str> = p
+                    .children
+                    .iter()
+                    .map(|&c| programs[c].name.as_str())
+                    .collect();
+                line.push_str(" -> ");
+                line.push_str(&children.join(", "));
+            }
+            line
+        })
+        .collect();
+
+    rng.shuffle(&mut lines);
+    (lines.join("\n"), balanced)
 }
 
 #[cfg(test)]
@@ -105,4 +197,14 @@ mod tests {
         let tree = Tree::from_str(IN).unwrap();
         assert_eq!(tree.solve(), 60);
     }
+
+    #[test]
+    fn test_generated() {
+        for seed in 0..20 {
+            let (input, balanced) = unbalanced_tower(&mut ::seeded_rng(seed), 200);
+            assert!(input.lines().count() > 100);
+            let tree = Tree::from_str(&input).unwrap();
+            assert_eq!(tree.solve(), balanced, "seed {}", seed);
+        }
+    }
 }
@@ -1,28 +1,21 @@
-use nom::{self, alphanumeric, space, types::CompleteStr as Input};
-use std::str;
+use nom::space;
 
-named!(
-    weight(Input) -> u32,
-    map_res!(
-            map!(delimited!(char!('('), is_not!(")"), char!(')')), |w| *w),
-        str::parse
-    )
-);
+use parsers::{self, identifier, identifiers, integer, Input};
+use Result;
+
+named!(weight(Input) -> u32, delimited!(char!('('), integer, char!(')')));
 
 named!(child_sep(Input) -> Input, complete!(tag!(" -> ")));
 
-named!(
-    children(Input) -> Vec<&str>,
-    separated_list!(tag!(", "), map!(alphanumeric, |c| *c))
-);
+named!(children(Input) -> Vec<&str>, call!(identifiers));
 
 named!(
     line(Input) -> (&str, u32, Vec<&str>),
-    do_parse!(n: alphanumeric >> opt!(space) >> w: weight >> opt!(child_sep) >> c: children >> (*n, w, c))
+    do_parse!(n: identifier >> opt!(space) >> w: weight >> opt!(child_sep) >> c: children >> (n, w, c))
 );
 
-pub fn parse_line(s: &str) -> Result<(&str, u32, Vec<&str>), nom::Err<Input>> {
-    line(Input(s)).map(|(_, r)| r)
+pub fn parse_lines(s: &str) -> Result<Vec<(&str, u32, Vec<&str>)>> {
+    parsers::lines(s, line)
 }
 
 #[cfg(test)]
@@ -31,10 +24,7 @@ mod tests {
 
     #[test]
     fn name_sample() {
-        assert_eq!(
-            alphanumeric(Input("pbga (66)")),
-            Ok((Input(" (66)"), Input("pbga")))
-        );
+        assert_eq!(identifier(Input("pbga (66)")), Ok((Input(" (66)"), "pbga")));
     }
 
     #[test]
@@ -1,6 +1,6 @@
 use fnv::FnvHashMap;
 
-use super::Result;
+use super::{Answers, Result};
 
 pub fn eval(input: &str) -> (i32, i32) {
     let mut env = FnvHashMap::default();
@@ -52,12 +52,10 @@ pub fn eval(input: &str) -> (i32, i32) {
     (first, second)
 }
 
-pub fn solve() -> Result<()> {
-    let program = super::get_input()?;
-    let (first, second) = eval(&program);
+pub fn solve(program: &str) -> Result<Answers> {
+    let (first, second) = eval(program);
 
-    println!("Day 8:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
@@ -1,4 +1,4 @@
-use super::Result;
+use super::{Answers, Result};
 
 pub fn process_stream(input: &str) -> Result<(u32, u32)> {
     let mut chars = input.trim().chars();
@@ -33,12 +33,10 @@ pub fn process_stream(input: &str) -> Result<(u32, u32)> {
     Ok((score, count))
 }
 
-pub fn solve() -> Result<()> {
-    let stream = super::get_input()?;
-    let (first, second) = process_stream(&stream)?;
+pub fn solve(stream: &str) -> Result<Answers> {
+    let (first, second) = process_stream(stream)?;
 
-    println!("Day 9:\nPart 1: {}\nPart 2: {}\n", first, second);
-    Ok(())
+    Ok(Answers::new(first, second))
 }
 
 #[cfg(test)]
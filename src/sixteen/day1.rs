@@ -1,74 +1,11 @@
 use failure::err_msg;
 use fnv::FnvHashSet as HashSet;
-use seventeen::Result;
-
-use self::Direction::{East, North, South, West};
-use self::Rotation::{Left, Right};
-
-#[derive(Clone, Copy, Debug)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
-
-impl Direction {
-    fn right(&self) -> Direction {
-        match *self {
-            North => East,
-            South => West,
-            East => South,
-            West => North,
-        }
-    }
-
-    fn left(&self) -> Direction {
-        match *self {
-            North => West,
-            South => East,
-            East => North,
-            West => South,
-        }
-    }
-
-    fn turn(&self, r: Rotation) -> Direction {
-        match r {
-            Right => self.right(),
-            Left => self.left(),
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
-enum Rotation {
-    Right,
-    Left,
-}
-
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-
-impl Point {
-    fn new() -> Point {
-        Point { x: 0, y: 0 }
-    }
-
-    fn distance(&self, p: Point) -> u32 {
-        ((self.x - p.x).abs() + (self.y - p.y).abs()) as u32
-    }
-
-    fn distance_from_start(&self) -> u32 {
-        self.distance(Point::new())
-    }
-
-    fn to_line(self, end: Point) -> Line {
-        Line { current: self, end }
-    }
-}
+use geom::Direction::North;
+use geom::Rotation::{Left, Right};
+use geom::{Direction, Point, Rotation};
+use image::{Image, Rgb};
+use seventeen::{Answers, Result};
+use tui::{self, Simulation};
 
 #[derive(Debug)]
 struct Position {
@@ -79,22 +16,13 @@ struct Position {
 impl Position {
     fn new() -> Position {
         Position {
-            direction: Direction::North,
-            location: Point::new(),
+            direction: North,
+            location: Point::origin(),
         }
     }
 
     fn travel(&self, k: i32) -> Point {
-        let Point { x, y } = self.location;
-
-        let (x, y) = match self.direction {
-            North => (x, y + k),
-            South => (x, y - k),
-            East => (x + k, y),
-            West => (x - k, y),
-        };
-
-        Point { x, y }
+        self.location.step_n(self.direction, k)
     }
 
     fn turn(&mut self, r: Rotation) {
@@ -117,6 +45,12 @@ struct Line {
     end: Point,
 }
 
+impl Line {
+    fn new(current: Point, end: Point) -> Line {
+        Line { current, end }
+    }
+}
+
 impl Iterator for Line {
     type Item = Point;
 
@@ -170,7 +104,7 @@ fn find_hq(instructions: &[Move]) -> Result<u32> {
         .into_iter()
         .fold(Position::new(), |mut pos: Position, mv| pos.exec(*mv));
 
-    Ok(position.location.distance_from_start())
+    Ok(position.location.manhattan(Point::origin()) as u32)
 }
 
 fn find_cycle(instructions: &[Move]) -> Result<u32> {
@@ -180,11 +114,11 @@ fn find_cycle(instructions: &[Move]) -> Result<u32> {
     for mv in instructions {
         let prev = current.location;
         current = current.exec(*mv);
-        let line = prev.to_line(current.location);
+        let line = Line::new(prev, current.location);
 
         for p in line {
             if !visited.insert(p) {
-                return Ok(p.distance_from_start());
+                return Ok(p.manhattan(Point::origin()) as u32);
             }
         }
     }
@@ -192,13 +126,119 @@ fn find_cycle(instructions: &[Move]) -> Result<u32> {
     Err(err_msg("no cycle present in input"))
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let instructions = parse_instructions(&input)?;
+pub fn solve(input: &str) -> Result<Answers> {
+    let instructions = parse_instructions(input)?;
     let part1 = find_hq(&instructions)?;
     let part2 = find_cycle(&instructions)?;
 
-    super::print_output(1, part1, part2)
+    Ok(Answers::new(part1, part2))
+}
+
+/// The walked path in grey, with every block visited before in red, the start
+/// in green and Easter Bunny HQ in blue.
+pub fn render(input: &str) -> Result<Image> {
+    let instructions = parse_instructions(input)?;
+    let mut current = Position::new();
+    let mut visited = HashSet::default();
+    let mut pixels = Vec::new();
+
+    for mv in instructions {
+        let prev = current.location;
+        current = current.exec(mv);
+
+        for p in Line::new(prev, current.location) {
+            let colour = if visited.insert(p) {
+                Rgb::GREY
+            } else {
+                Rgb::RED
+            };
+            pixels.push((p, colour));
+        }
+    }
+
+    pixels.push((Point::origin(), Rgb::GREEN));
+    pixels.push((current.location, Rgb::BLUE));
+
+    Ok(Image::from_points(pixels, Rgb::BLACK).scale(2))
+}
+
+/// The walk one block at a time, remembering where it has been.
+struct Walk {
+    moves: Vec<Move>,
+    next: usize,
+    position: Position,
+    remaining: i32,
+    visited: HashSet<Point>,
+    twice: Option<Point>,
+}
+
+impl Simulation for Walk {
+    fn step(&mut self) -> bool {
+        while self.remaining <= 0 {
+            let (rotation, distance) = match self.moves.get(self.next) {
+                Some(&mv) => mv,
+                None => return false,
+            };
+
+            self.next += 1;
+            self.position.turn(rotation);
+            self.remaining = distance;
+        }
+
+        let location = self.position.travel(1);
+        self.position.location = location;
+        self.remaining -= 1;
+        if !self.visited.insert(location) && self.twice.is_none() {
+            self.twice = Some(location);
+        }
+
+        true
+    }
+
+    fn cell(&self, p: Point) -> char {
+        if p == self.position.location {
+            tui::arrow(self.position.direction)
+        } else if Some(p) == self.twice {
+            'X'
+        } else if p == Point::origin() {
+            'S'
+        } else if self.visited.contains(&p) {
+            '.'
+        } else {
+            ' '
+        }
+    }
+
+    fn focus(&self) -> Point {
+        self.position.location
+    }
+
+    fn status(&self) -> Vec<(&'static str, String)> {
+        let Point { x, y } = self.position.location;
+        let away = self.position.location.manhattan(Point::origin());
+        let twice = self
+            .twice
+            .map_or("-".to_owned(), |p| p.manhattan(Point::origin()).to_string());
+
+        vec![
+            ("position", format!("{},{}", x, y)),
+            ("blocks away", away.to_string()),
+            ("first visited twice", twice),
+        ]
+    }
+}
+
+/// The walk to Easter Bunny HQ, with the first block visited twice marked
+/// with an X.
+pub fn simulation(input: &str) -> Result<Box<Simulation>> {
+    Ok(Box::new(Walk {
+        moves: parse_instructions(input)?,
+        next: 0,
+        position: Position::new(),
+        remaining: 0,
+        visited: HashSet::default(),
+        twice: None,
+    }))
 }
 
 #[cfg(test)]
@@ -1,71 +1,46 @@
 mod parsing;
 
 use self::parsing::parse_directions;
-use super::Result;
+use super::{Answers, Result};
+use geom::{Direction, Point};
+use grid::Grid;
 
-type Position = (usize, usize);
+const START1: Point = Point { x: 1, y: 1 };
+const KEYPAD1: &str = "123\n456\n789";
 
-const START1: Position = (2, 2);
-const KEYPAD1: &[&[char]] = &[
-    &['0', '0', '0', '0', '0'],
-    &['0', '1', '2', '3', '0'],
-    &['0', '4', '5', '6', '0'],
-    &['0', '7', '8', '9', '0'],
-    &['0', '0', '0', '0', '0'],
-];
+const START2: Point = Point { x: 0, y: 2 };
+const KEYPAD2: &str = "  1  \n 234 \n56789\n ABC \n  D  ";
 
-const START2: Position = (1, 3);
-const KEYPAD2: &[&[char]] = &[
-    &['0', '0', '0', '0', '0', '0', '0'],
-    &['0', '0', '0', '1', '0', '0', '0'],
-    &['0', '0', '2', '3', '4', '0', '0'],
-    &['0', '5', '6', '7', '8', '9', '0'],
-    &['0', '0', 'A', 'B', 'C', '0', '0'],
-    &['0', '0', '0', 'D', '0', '0', '0'],
-    &['0', '0', '0', '0', '0', '0', '0'],
-];
+/// A keypad layout, with spaces where there are no buttons.
+type Keypad = Grid<Option<char>>;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+fn keypad(layout: &str) -> Result<Keypad> {
+    Grid::from_chars(layout, |c| Ok(if c == ' ' { None } else { Some(c) }))
 }
 
-fn bathroom_code(keypad: &[&[char]], start: Position, input: &[Vec<Direction>]) -> String {
-    let (mut x, mut y) = start;
+fn bathroom_code(keypad: &Keypad, start: Point, input: &[Vec<Direction>]) -> String {
+    let mut p = start;
     input
         .into_iter()
         .map(|directions| {
-            directions.into_iter().for_each(|d| {
-                use self::Direction::*;
-                let (x_, y_) = match d {
-                    Up => (x, y - 1),
-                    Down => (x, y + 1),
-                    Left => (x - 1, y),
-                    Right => (x + 1, y),
-                };
-
-                if keypad[y_][x_] != '0' {
-                    x = x_;
-                    y = y_;
+            directions.into_iter().for_each(|&d| {
+                if let Some(&Some(_)) = keypad.get(p.step(d)) {
+                    p = p.step(d);
                 }
             });
 
-            keypad[y][x]
+            keypad[p].expect("started off the keypad")
         })
         .collect()
 }
 
-pub fn solve() -> Result<()> {
-    let input = super::get_input()?;
-    let directions = parse_directions(&input)?;
+pub fn solve(input: &str) -> Result<Answers> {
+    let directions = parse_directions(input)?;
 
-    let part1 = bathroom_code(KEYPAD1, START1, &directions);
-    let part2 = bathroom_code(KEYPAD2, START2, &directions);
+    let part1 = bathroom_code(&keypad(KEYPAD1)?, START1, &directions);
+    let part2 = bathroom_code(&keypad(KEYPAD2)?, START2, &directions);
 
-    super::print_output(2, part1, part2)
+    Ok(Answers::new(part1, part2))
 }
 
 #[cfg(test)]
@@ -75,14 +50,14 @@ mod test {
     #[test]
     fn simple_bathroom_code() {
         let input = parse_directions("ULL\nRRDDD\nLURDL\nUUUUD\n").unwrap();
-        let result = bathroom_code(KEYPAD1, START1, &input);
+        let result = bathroom_code(&keypad(KEYPAD1).unwrap(), START1, &input);
         assert_eq!(result, "1985".to_string());
     }
 
     #[test]
     fn complex_bathroom_code() {
         let input = parse_directions("ULL\nRRDDD\nLURDL\nUUUUD\n").unwrap();
-        let result = bathroom_code(KEYPAD2, START2, &input);
+        let result = bathroom_code(&keypad(KEYPAD2).unwrap(), START2, &input);
         assert_eq!(result, "5DB3".to_string());
     }
 }
@@ -1,10 +1,11 @@
-use super::Direction;
+use geom::Direction;
 use nom::{line_ending, types::CompleteStr as Input};
+use parsers;
 
-named!(up(Input) -> Direction, value!(Direction::Up, char!('U')));
-named!(down(Input) -> Direction, value!(Direction::Down, char!('D')));
-named!(left(Input) -> Direction, value!(Direction::Left, char!('L')));
-named!(right(Input) -> Direction, value!(Direction::Right, char!('R')));
+named!(up(Input) -> Direction, value!(Direction::North, char!('U')));
+named!(down(Input) -> Direction, value!(Direction::South, char!('D')));
+named!(left(Input) -> Direction, value!(Direction::West, char!('L')));
+named!(right(Input) -> Direction, value!(Direction::East, char!('R')));
 
 named!(direction(Input) -> Direction, alt!(up | down | left | right));
 
@@ -13,22 +14,20 @@ named!(line(Input) -> Vec<Direction>, many1!(direction));
 named!(lines(Input) -> Vec<Vec<Direction>>, separated_list!(line_ending, line));
 
 pub fn parse_directions(s: &str) -> super::Result<Vec<Vec<Direction>>> {
-    lines(Input(s))
-        .map(|(_rest, result)| result)
-        .map_err(|e| format_err!("failed to parse input: {}", e))
+    parsers::parse(s.trim(), lines)
 }
 
 #[cfg(test)]
 mod test {
-    use super::Direction::*;
     use super::*;
+    use geom::Direction::*;
 
     #[test]
     fn simple_directions<'a>() {
-        assert_eq!(direction(Input("U")), Ok(("".into(), Up)));
-        assert_eq!(direction(Input("D")), Ok(("".into(), Down)));
-        assert_eq!(direction(Input("L")), Ok(("".into(), Left)));
-        assert_eq!(direction(Input("R")), Ok(("".into(), Right)));
+        assert_eq!(direction(Input("U")), Ok(("".into(), North)));
+        assert_eq!(direction(Input("D")), Ok(("".into(), South)));
+        assert_eq!(direction(Input("L")), Ok(("".into(), West)));
+        assert_eq!(direction(Input("R")), Ok(("".into(), East)));
     }
 
     #[test]
@@ -38,7 +37,7 @@ mod test {
             line(input),
             Ok((
                 "".into(),
-                vec![Up, Down, Left, Left, Right, Right, Down, Up]
+                vec![North, South, West, West, East, East, South, North]
             ))
         );
     }
@@ -48,7 +47,7 @@ mod test {
         let input = Input("UDLR\nUDLR\n");
         assert_eq!(
             lines(input),
-            Ok(("\n".into(), vec![vec![Up, Down, Left, Right]; 2]))
+            Ok(("\n".into(), vec![vec![North, South, West, East]; 2]))
         )
     }
 }
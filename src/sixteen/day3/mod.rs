@@ -1,7 +1,7 @@
 mod parsing;
 
 use self::parsing::parse_triangles;
-use super::Result;
+use super::{Answers, Result};
 
 type Triangle = [u16; 3];
 
@@ -23,13 +23,12 @@ fn count_valid_vertical(triangles: Vec<Triangle>) -> u32 {
     count_valid(&swapped)
 }
 
-pub fn solve() -> Result<()> {
-    let input = ::get_input()?;
-    let triangles = parse_triangles(&input)?;
+pub fn solve(input: &str) -> Result<Answers> {
+    let triangles = parse_triangles(input)?;
     let part1 = count_valid(&triangles);
     let part2 = count_valid_vertical(triangles);
 
-    ::print_output(3, part1, part2)
+    Ok(Answers::new(part1, part2))
 }
 
 #[cfg(test)]
@@ -1,14 +1,13 @@
 use super::Triangle;
 use nom::{digit, line_ending, space, types::CompleteStr as Input};
+use parsers;
 use std::str;
 
 named!(triangle(Input) -> Triangle, count_fixed!(u16, map_res!(preceded!(space, digit), |d: Input| d.parse()), 3));
 named!(lines(Input) -> Vec<Triangle>, separated_list!(line_ending, triangle));
 
 pub fn parse_triangles(s: &str) -> ::Result<Vec<Triangle>> {
-    lines(Input(s))
-        .map(|(_rest, result)| result)
-        .map_err(|e| format_err!("failed to parse input: {}", e))
+    parsers::parse(s.trim_right(), lines)
 }
 
 #[cfg(test)]
@@ -1,5 +1,7 @@
 use failure;
 use itertools::Itertools;
+use rand::prng::XorShiftRng;
+use rand::Rng;
 use std::{
     collections::HashMap,
     str::{self, FromStr},
@@ -87,13 +89,78 @@ fn find_storage(rooms: &[Room]) -> Option<u32> {
         .map(|r| r.sector)
 }
 
-pub fn solve() -> ::Result<()> {
-    let input = ::get_input()?;
-    let rooms = Room::parse_many(&input)?;
+pub fn solve(input: &str) -> ::Result<::Answers> {
+    let rooms = Room::parse_many(input)?;
     let part1 = sector_sum(&rooms);
     let part2 = find_storage(&rooms).unwrap();
 
-    ::print_output(4, part1, part2)
+    Ok(::Answers::new(part1, part2))
+}
+
+fn checksum(encoded_name: &str) -> String {
+    count_chars(encoded_name.chars())
+        .into_iter()
+        .sorted_by(|(char1, val1), (char2, val2)| val2.cmp(val1).then(char1.cmp(char2)))
+        .into_iter()
+        .take(5)
+        .map(|(c, _)| c)
+        .collect()
+}
+
+/// `size` rooms, mostly real with some decoys, and exactly one of them
+/// holding the North Pole objects.
+pub fn generate(rng: &mut XorShiftRng, size: usize) -> String {
+    fn encrypt(name: &str, sector: u32) -> String {
+        name.chars()
+            .map(|c| match c {
+                '-' => '-',
+                c => ((c as u8 - b'a' + 26 - (sector % 26) as u8) % 26 + b'a') as char,
+            })
+            .collect()
+    }
+
+    let storage = rng.gen_range(0, size.max(1));
+    let mut rooms = Vec::new();
+
+    for i in 0..size.max(1) {
+        let sector = rng.gen_range(100, 1000);
+        let name = if i == storage {
+            encrypt("northpole-object-storage", sector)
+        } else {
+            loop {
+                let words: Vec<String> = (0..rng.gen_range(2, 6))
+                    .map(|_| {
+                        (0..rng.gen_range(3, 10))
+                            .map(|_| rng.gen_range(b'a', b'z' + 1) as char)
+                            .collect()
+                    })
+                    .collect();
+                let name = words.join("-");
+                let distinct = count_chars(name.chars()).len();
+                if distinct >= 5 && !shift_name(&name, sector).contains("north") {
+                    break name;
+                }
+            }
+        };
+
+        let real = checksum(&name);
+        let checksum = if i == storage || rng.gen_bool(0.7) {
+            real
+        } else {
+            loop {
+                let mut letters: Vec<char> = (b'a'..b'z' + 1).map(|c| c as char).collect();
+                rng.shuffle(&mut letters);
+                let decoy: String = letters[..5].iter().collect();
+                if decoy != real {
+                    break decoy;
+                }
+            }
+        };
+
+        rooms.push(format!("{}-{}[{}]", name, sector, checksum));
+    }
+
+    rooms.join("\n")
 }
 
 impl FromStr for Room {
@@ -155,6 +222,14 @@ mod test {
         assert_eq!(result, 1514)
     }
 
+    #[test]
+    fn generated_rooms() {
+        let rooms = Room::parse_many(&generate(&mut ::seeded_rng(4), 100)).unwrap();
+        assert_eq!(rooms.len(), 100);
+        assert!(rooms.iter().filter(|r| r.is_real()).count() > 50);
+        assert!(find_storage(&rooms).is_some());
+    }
+
     #[test]
     fn simple_name_shift() {
         assert_eq!(
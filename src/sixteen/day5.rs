@@ -46,8 +46,7 @@ fn ordered_bruteforce(door_id: &str) -> String {
 }
 
 
-pub fn solve() -> ::Result<()> {
-    let input = ::get_input()?;
+pub fn solve(input: &str) -> ::Result<::Answers> {
     let door_id = input.trim();
 
     info!("Solving part 1");
@@ -56,7 +55,7 @@ pub fn solve() -> ::Result<()> {
     info!("Solving part 2");
     let part2 = ordered_bruteforce(door_id);
 
-    ::print_output(5, part1, part2)
+    Ok(::Answers::new(part1, part2))
 }
 
 #[cfg(test)]
@@ -29,9 +29,8 @@ fn recover_message(msg: &[Vec<char>], use_modified_code: bool) -> String {
         .collect()
 }
 
-pub fn solve() -> ::Result<()> {
-    let input = ::get_input()?;
-    let messages = parse_messages(&input)?;
+pub fn solve(input: &str) -> ::Result<::Answers> {
+    let messages = parse_messages(input)?;
 
     info!("Solving part 1");
     let part1 = recover_message(&messages, true);
@@ -39,7 +38,7 @@ pub fn solve() -> ::Result<()> {
     info!("Solving part 2");
     let part2 = recover_message(&messages, false);
 
-    ::print_output(6, part1, part2)
+    Ok(::Answers::new(part1, part2))
 }
 
 #[cfg(test)]
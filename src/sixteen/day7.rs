@@ -69,16 +69,14 @@ fn count_ssl(addresses: &str) -> usize {
     addresses.trim().lines().filter(|a| verify_ssl(a)).count()
 }
 
-pub fn solve() -> ::Result<()> {
-    let input = ::get_input()?;
-
+pub fn solve(input: &str) -> ::Result<::Answers> {
     info!("Solving part 1");
-    let part1 = count_tls(&input);
+    let part1 = count_tls(input);
 
     info!("Solving part 2");
-    let part2 = count_ssl(&input);
+    let part2 = count_ssl(input);
 
-    ::print_output(6, part1, part2)
+    Ok(::Answers::new(part1, part2))
 }
 
 #[cfg(test)]
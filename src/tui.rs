@@ -0,0 +1,305 @@
+//! Watching the days that simulate something step by step, in the terminal.
+//!
+//! The view follows the current position of the simulation, with its
+//! counters below it. Space plays or pauses, `s` or the right arrow takes a
+//! single step, `+` and `-` change the speed and `q` quits.
+
+use std::io::Write;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::screen::AlternateScreen;
+use termion::{clear, cursor, get_tty, terminal_size};
+
+use geom::Direction::{self, East, North, South, West};
+use geom::Point;
+use params::Overrides;
+use {missing, Result};
+
+/// Time between two frames while playing.
+const FRAME_MILLIS: u64 = 50;
+
+/// Playing never gets faster than this many steps per frame.
+const MAX_SPEED: usize = 1 << 20;
+
+/// Lines below the view, for the counters and the controls.
+const STATUS_LINES: usize = 2;
+
+/// Something that can be watched one step at a time.
+pub trait Simulation {
+    /// Takes a single step, or returns false once there are none left.
+    fn step(&mut self) -> bool;
+
+    /// What to show at `p`.
+    fn cell(&self, p: Point) -> char;
+
+    /// The position the view follows.
+    fn focus(&self) -> Point;
+
+    /// The counters to show, by name.
+    fn status(&self) -> Vec<(&'static str, String)>;
+}
+
+/// An arrow pointing the way something is facing.
+pub fn arrow(d: Direction) -> char {
+    match d {
+        North => '^',
+        East => '>',
+        South => 'v',
+        West => '<',
+    }
+}
+
+/// Whether the simulation runs on its own, and how fast.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Player {
+    pub playing: bool,
+    /// Steps per frame while playing
+    pub speed: usize,
+    /// Steps taken so far
+    pub steps: usize,
+    /// The simulation has no steps left
+    pub done: bool,
+}
+
+impl Player {
+    pub fn new() -> Player {
+        Player::default()
+    }
+
+    /// Reacts to a key, returning false if it asks to quit.
+    pub fn key(&mut self, key: Key, sim: &mut Simulation) -> bool {
+        match key {
+            Key::Char('q') | Key::Esc | Key::Ctrl('c') => return false,
+            Key::Char(' ') => self.playing = !self.playing && !self.done,
+            Key::Char('s') | Key::Right => {
+                self.playing = false;
+                self.advance(sim, 1);
+            }
+            Key::Char('+') | Key::Up => self.speed = (self.speed * 2).min(MAX_SPEED),
+            Key::Char('-') | Key::Down => self.speed = (self.speed / 2).max(1),
+            _ => (),
+        }
+
+        true
+    }
+
+    /// Takes a frame's worth of steps, if playing.
+    pub fn tick(&mut self, sim: &mut Simulation) {
+        if self.playing {
+            let n = self.speed;
+            self.advance(sim, n);
+        }
+    }
+
+    fn advance(&mut self, sim: &mut Simulation, n: usize) {
+        for _ in 0..n {
+            if !sim.step() {
+                self.done = true;
+                self.playing = false;
+                return;
+            }
+
+            self.steps += 1;
+        }
+    }
+}
+
+impl Default for Player {
+    fn default() -> Player {
+        Player {
+            playing: false,
+            speed: 1,
+            steps: 0,
+            done: false,
+        }
+    }
+}
+
+/// The lines on a `width` by `height` terminal: the view centred on the
+/// focus of the simulation, then its counters and the controls.
+pub fn screen(sim: &Simulation, player: &Player, width: usize, height: usize) -> Vec<String> {
+    let rows = height.saturating_sub(STATUS_LINES);
+    let corner = sim.focus() - Point::new(width as i32 / 2, rows as i32 / 2);
+
+    let mut lines: Vec<String> = (0..rows as i32)
+        .map(|y| {
+            (0..width as i32)
+                .map(|x| sim.cell(corner + Point::new(x, y)))
+                .collect()
+        })
+        .collect();
+
+    let mut counters = vec![format!("step {}", player.steps)];
+    counters.extend(
+        sim.status()
+            .into_iter()
+            .map(|(name, value)| format!("{} {}", name, value)),
+    );
+
+    let state = if player.done {
+        "done"
+    } else if player.playing {
+        "playing"
+    } else {
+        "paused"
+    };
+    let controls = format!(
+        "{}, {} steps per frame | space play/pause, s step, +/- speed, q quit",
+        state, player.speed
+    );
+
+    lines.push(counters.join(" | "));
+    lines.push(controls);
+    lines
+        .into_iter()
+        .map(|line| line.chars().take(width).collect())
+        .collect()
+}
+
+/// Takes over the terminal until the user quits.
+pub fn run(sim: &mut Simulation) -> Result<()> {
+    // The input itself may come from stdin, so keys are read from the
+    // terminal directly
+    let tty = get_tty()?;
+    let keys = tty.try_clone()?;
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for key in keys.keys() {
+            match key {
+                Ok(key) if sender.send(key).is_ok() => (),
+                _ => return,
+            }
+        }
+    });
+
+    let mut out = AlternateScreen::from(tty.into_raw_mode()?);
+    write!(out, "{}{}", cursor::Hide, clear::All)?;
+
+    let mut player = Player::new();
+    let frame = Duration::from_millis(FRAME_MILLIS);
+    loop {
+        let (width, height) = terminal_size().unwrap_or((80, 24));
+        let lines = screen(sim, &player, width as usize, height as usize);
+        write!(out, "{}", cursor::Goto(1, 1))?;
+        for (i, line) in lines.iter().enumerate() {
+            let end = if i + 1 < lines.len() { "\r\n" } else { "" };
+            write!(out, "{}{}{}", line, clear::UntilNewline, end)?;
+        }
+        out.flush()?;
+
+        match receiver.recv_timeout(frame) {
+            Ok(key) => {
+                if !player.key(key, sim) {
+                    break;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => player.tick(sim),
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    write!(out, "{}", cursor::Show)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Sets up the simulation of a day, with the same parameters as its solver.
+pub type Watcher = fn(&str, &Overrides) -> Result<Box<Simulation>>;
+
+pub fn watcher(edition: u32, day: u32) -> Option<Watcher> {
+    use {seventeen, sixteen};
+
+    let watcher: Watcher = match (edition, day) {
+        (2016, 1) => plain!(sixteen::day1::simulation),
+        (2017, 19) => plain!(seventeen::day19::simulation),
+        (2017, 22) => tuned!(seventeen::day22::simulation),
+        (2017, 25) => plain!(seventeen::day25::simulation),
+        _ => return None,
+    };
+
+    Some(watcher)
+}
+
+pub fn watch(edition: u32, day: u32, input: &str, overrides: &Overrides) -> Result<()> {
+    match watcher(edition, day) {
+        Some(simulation) => run(&mut *simulation(input, overrides)?),
+        None => Err(missing("simulation to watch", edition, day)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Walks east along a row of dots, for a fixed number of steps.
+    struct Walk {
+        x: i32,
+        end: i32,
+    }
+
+    impl Simulation for Walk {
+        fn step(&mut self) -> bool {
+            self.x += 1;
+            self.x <= self.end
+        }
+
+        fn cell(&self, p: Point) -> char {
+            match (p.x == self.x, p.y == 0) {
+                (true, true) => arrow(East),
+                (false, true) => '.',
+                _ => ' ',
+            }
+        }
+
+        fn focus(&self) -> Point {
+            Point::new(self.x, 0)
+        }
+
+        fn status(&self) -> Vec<(&'static str, String)> {
+            vec![("x", self.x.to_string())]
+        }
+    }
+
+    #[test]
+    fn controls() {
+        let mut walk = Walk { x: 0, end: 10 };
+        let mut player = Player::new();
+
+        player.tick(&mut walk);
+        assert_eq!(player.steps, 0);
+        assert!(player.key(Key::Char('s'), &mut walk));
+        assert_eq!((player.steps, walk.x), (1, 1));
+
+        player.key(Key::Char('+'), &mut walk);
+        player.key(Key::Char('+'), &mut walk);
+        player.key(Key::Char(' '), &mut walk);
+        player.tick(&mut walk);
+        assert_eq!((player.steps, player.speed), (5, 4));
+
+        player.tick(&mut walk);
+        player.tick(&mut walk);
+        assert!(player.done && !player.playing);
+        assert_eq!(player.steps, 10);
+
+        player.key(Key::Char(' '), &mut walk);
+        assert!(!player.playing);
+        assert!(!player.key(Key::Char('q'), &mut walk));
+    }
+
+    #[test]
+    fn view() {
+        let walk = Walk { x: 3, end: 10 };
+        let player = Player::new();
+        let lines = screen(&walk, &player, 7, 5);
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "       ");
+        assert_eq!(lines[1], "...>...");
+        assert_eq!(lines[3], "step 0 ");
+        assert!(lines[4].starts_with("paused"));
+    }
+}
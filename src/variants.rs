@@ -0,0 +1,205 @@
+use std::fmt;
+use std::panic;
+use std::result;
+
+use rand::prng::XorShiftRng;
+
+use batch::{panic_message, quietly};
+use {seeded_rng, Answer, Result};
+
+/// One of several implementations of the same puzzle part.
+pub struct Variant {
+    pub part: u8,
+    pub name: &'static str,
+    pub solve: fn(&str) -> Result<Answer>,
+}
+
+/// The implementations of a day that should always agree, and the inputs to
+/// hold them to that.
+pub struct Suite {
+    pub variants: Vec<Variant>,
+    /// Inputs known to be valid, usually the examples from the puzzle text
+    pub examples: Vec<&'static str>,
+    /// Produces random valid inputs
+    pub generate: Option<fn(&mut XorShiftRng) -> String>,
+}
+
+/// All variants of one part, run on the same input.
+#[derive(Debug, Serialize)]
+pub struct Case {
+    pub input: String,
+    pub name: String,
+    pub part: u8,
+    pub results: Vec<(&'static str, result::Result<Answer, String>)>,
+}
+
+impl Case {
+    /// Variants agree if they all produce the same answer, or all reject the input.
+    pub fn agrees(&self) -> bool {
+        let mut results = self.results.iter().map(|(_, r)| r);
+        match results.next() {
+            Some(Ok(first)) => results.all(|r| r.as_ref().ok() == Some(first)),
+            Some(Err(_)) => results.all(|r| r.is_err()),
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub checked: usize,
+    pub divergences: Vec<Case>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for case in &self.divergences {
+            writeln!(f, "{}, part {} diverges:", case.name, case.part)?;
+            for (name, result) in &case.results {
+                match result {
+                    Ok(answer) => writeln!(f, "  {:<12} {}", name, answer)?,
+                    Err(e) => writeln!(f, "  {:<12} ERROR: {}", name, e)?,
+                }
+            }
+
+            writeln!(f, "  input:")?;
+            for line in case.input.lines() {
+                writeln!(f, "    {}", line)?;
+            }
+        }
+
+        writeln!(
+            f,
+            "{} of {} comparisons diverged",
+            self.divergences.len(),
+            self.checked
+        )
+    }
+}
+
+fn run_variant(variant: &Variant, input: &str) -> result::Result<Answer, String> {
+    match panic::catch_unwind(|| (variant.solve)(input)) {
+        Ok(Ok(answer)) => Ok(answer),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(payload) => Err(format!("panicked: {}", panic_message(&*payload))),
+    }
+}
+
+/// Runs every variant on the examples and on `cases` generated inputs.
+pub fn compare(suite: &Suite, cases: usize, seed: u64) -> Report {
+    let mut inputs: Vec<(String, String)> = suite
+        .examples
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (format!("example {}", i + 1), s.to_string()))
+        .collect();
+
+    if let Some(generate) = suite.generate {
+        let mut rng = seeded_rng(seed);
+        inputs.extend((0..cases).map(|i| (format!("generated {}", i + 1), generate(&mut rng))));
+    }
+
+    let mut parts: Vec<u8> = suite.variants.iter().map(|v| v.part).collect();
+    parts.sort();
+    parts.dedup();
+
+    quietly(|| {
+        let mut checked = 0;
+        let mut divergences = Vec::new();
+
+        for (name, input) in inputs {
+            for &part in &parts {
+                let results = suite
+                    .variants
+                    .iter()
+                    .filter(|v| v.part == part)
+                    .map(|v| (v.name, run_variant(v, &input)))
+                    .collect();
+
+                let case = Case {
+                    input: input.clone(),
+                    name: name.clone(),
+                    part,
+                    results,
+                };
+
+                checked += 1;
+                if !case.agrees() {
+                    divergences.push(case);
+                }
+            }
+        }
+
+        Report {
+            checked,
+            divergences,
+        }
+    })
+}
+
+/// The days that have alternative implementations registered.
+pub fn suite(edition: u32, day: u32) -> Option<Suite> {
+    use seventeen::*;
+
+    match (edition, day) {
+        (2017, 17) => Some(day17::variants()),
+        (2017, 18) => Some(day18::variants()),
+        (2017, 23) => Some(day23::variants()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn double(s: &str) -> Result<Answer> {
+        let x: u32 = s.trim().parse()?;
+        Ok((x * 2).into())
+    }
+
+    fn add(s: &str) -> Result<Answer> {
+        let x: u32 = s.trim().parse()?;
+        Ok((x + x).into())
+    }
+
+    fn square(s: &str) -> Result<Answer> {
+        let x: u32 = s.trim().parse()?;
+        Ok((x * x).into())
+    }
+
+    fn variant(name: &'static str, solve: fn(&str) -> Result<Answer>) -> Variant {
+        Variant {
+            part: 1,
+            name,
+            solve,
+        }
+    }
+
+    #[test]
+    fn agreeing_variants() {
+        let suite = Suite {
+            variants: vec![variant("double", double), variant("add", add)],
+            examples: vec!["1", "x"],
+            generate: None,
+        };
+
+        let report = compare(&suite, 10, 0);
+        assert_eq!(report.checked, 2);
+        assert!(report.divergences.is_empty());
+    }
+
+    #[test]
+    fn diverging_variants() {
+        let suite = Suite {
+            variants: vec![variant("double", double), variant("square", square)],
+            examples: vec!["2"],
+            generate: Some(|_| "3".to_owned()),
+        };
+
+        let report = compare(&suite, 2, 0);
+        assert_eq!(report.checked, 3);
+        let names: Vec<&str> = report.divergences.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["generated 1", "generated 2"]);
+    }
+}
@@ -0,0 +1,439 @@
+//! The register machine of the assembly puzzles, such as
+//!
+//! ```text
+//! set a 1
+//! add a 2
+//! jgz a -1
+//! ```
+//!
+//! Registers are single letters starting at zero, and operands are either a
+//! register or a number, and `#` starts a comment. Each puzzle allows its own
+//! set of instructions, and plugs what `snd` and `rcv` mean, or what to watch,
+//! into an `Extension`.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use nom::{alpha, anychar, space};
+
+use parsers::{self, integer, Input};
+use Result;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reg(pub u8);
+
+impl fmt::Display for Reg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0 as char)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operand {
+    Reg(u8),
+    Val(i64),
+}
+
+impl Operand {
+    pub fn eval(&self, regs: &Registers) -> i64 {
+        match *self {
+            Operand::Reg(reg) => regs[Reg(reg)],
+            Operand::Val(v) => v,
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Operand::Reg(reg) => write!(f, "{}", Reg(reg)),
+            Operand::Val(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Inst {
+    Set(Reg, Operand),
+    Add(Reg, Operand),
+    Sub(Reg, Operand),
+    Mul(Reg, Operand),
+    Mod(Reg, Operand),
+    /// Jumps by the second operand if the first is positive
+    Jgz(Operand, Operand),
+    /// Jumps by the second operand if the first isn't zero
+    Jnz(Operand, Operand),
+    Snd(Operand),
+    Rcv(Reg),
+}
+
+impl Inst {
+    fn new(mnemonic: &str, args: &[Operand]) -> Result<Inst> {
+        use self::Inst::*;
+
+        let reg = |i: usize| match args[i] {
+            Operand::Reg(r) => Ok(Reg(r)),
+            Operand::Val(v) => Err(format_err!("{} needs a register, not {}", mnemonic, v)),
+        };
+
+        let arity = match mnemonic {
+            "snd" | "rcv" => 1,
+            "set" | "add" | "sub" | "mul" | "mod" | "jgz" | "jnz" => 2,
+            other => bail!("unknown instruction: {}", other),
+        };
+        ensure!(
+            args.len() == arity,
+            "{} takes {} operands, not {}",
+            mnemonic,
+            arity,
+            args.len()
+        );
+
+        let inst = match mnemonic {
+            "set" => Set(reg(0)?, args[1]),
+            "add" => Add(reg(0)?, args[1]),
+            "sub" => Sub(reg(0)?, args[1]),
+            "mul" => Mul(reg(0)?, args[1]),
+            "mod" => Mod(reg(0)?, args[1]),
+            "jgz" => Jgz(args[0], args[1]),
+            "jnz" => Jnz(args[0], args[1]),
+            "snd" => Snd(args[0]),
+            _ => Rcv(reg(0)?),
+        };
+
+        Ok(inst)
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        use self::Inst::*;
+
+        match *self {
+            Set(..) => "set",
+            Add(..) => "add",
+            Sub(..) => "sub",
+            Mul(..) => "mul",
+            Mod(..) => "mod",
+            Jgz(..) => "jgz",
+            Jnz(..) => "jnz",
+            Snd(..) => "snd",
+            Rcv(..) => "rcv",
+        }
+    }
+
+    /// The offset of a jump, or nothing for the other instructions.
+    pub fn jump_offset(&self) -> Option<Operand> {
+        match *self {
+            Inst::Jgz(_, offset) | Inst::Jnz(_, offset) => Some(offset),
+            _ => None,
+        }
+    }
+}
+
+/// The instruction as `parse` reads it, such as `jgz a -2`.
+impl fmt::Display for Inst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::Inst::*;
+
+        let name = self.mnemonic();
+        match *self {
+            Set(reg, arg) | Add(reg, arg) | Sub(reg, arg) | Mul(reg, arg) | Mod(reg, arg) => {
+                write!(f, "{} {} {}", name, reg, arg)
+            }
+            Jgz(cond, offset) | Jnz(cond, offset) => write!(f, "{} {} {}", name, cond, offset),
+            Snd(arg) => write!(f, "{} {}", name, arg),
+            Rcv(reg) => write!(f, "{} {}", name, reg),
+        }
+    }
+}
+
+named!(
+    operand(Input) -> Operand,
+    alt!(
+        map!(integer, Operand::Val) |
+        map!(verify!(anychar, |c: char| c.is_ascii_lowercase()), |c| Operand::Reg(c as u8))
+    )
+);
+
+named!(
+    line(Input) -> (&str, Vec<Operand>),
+    do_parse!(
+        mnemonic: alpha >>
+        args: many0!(preceded!(space, operand)) >>
+        (*mnemonic, args)
+    )
+);
+
+/// Parses a program that may only use the instructions in `allowed`.
+pub fn parse(input: &str, allowed: &[&str]) -> Result<Vec<Inst>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i, l.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(i, l)| {
+            parsers::parse(l, line)
+                .and_then(|(mnemonic, args)| {
+                    ensure!(
+                        allowed.contains(&mnemonic),
+                        "{} is not one of the instructions {}",
+                        mnemonic,
+                        allowed.join(", ")
+                    );
+                    Inst::new(mnemonic, &args)
+                })
+                .map_err(|e| format_err!("line {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+/// One register for every byte, although programs only name letters.
+#[derive(Clone)]
+pub struct Registers([i64; 256]);
+
+impl Registers {
+    pub fn new() -> Registers {
+        Registers([0; 256])
+    }
+}
+
+impl Default for Registers {
+    fn default() -> Registers {
+        Registers::new()
+    }
+}
+
+impl Index<Reg> for Registers {
+    type Output = i64;
+
+    fn index(&self, Reg(reg): Reg) -> &i64 {
+        &self.0[reg as usize]
+    }
+}
+
+impl IndexMut<Reg> for Registers {
+    fn index_mut(&mut self, Reg(reg): Reg) -> &mut i64 {
+        &mut self.0[reg as usize]
+    }
+}
+
+impl Index<char> for Registers {
+    type Output = i64;
+
+    fn index(&self, reg: char) -> &i64 {
+        &self[Reg(reg as u8)]
+    }
+}
+
+impl IndexMut<char> for Registers {
+    fn index_mut(&mut self, reg: char) -> &mut i64 {
+        &mut self[Reg(reg as u8)]
+    }
+}
+
+/// What `rcv` does to its register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Receive {
+    Store(i64),
+    /// Leaves the register alone
+    Skip,
+    /// Stops before the `rcv`, so it runs again when the machine is resumed
+    Halt,
+}
+
+/// The parts of the machine that depend on the puzzle.
+pub trait Extension {
+    /// Called before every instruction.
+    fn before(&mut self, _inst: &Inst) {}
+
+    /// Sends the value of `snd`.
+    fn send(&mut self, _value: i64) {}
+
+    /// Receives for `rcv`, given what its register holds.
+    fn receive(&mut self, _value: i64) -> Receive {
+        Receive::Skip
+    }
+}
+
+/// A machine without anything to send to or receive from.
+impl Extension for () {}
+
+#[derive(Clone)]
+pub struct Machine<'a, E> {
+    program: &'a [Inst],
+    pub regs: Registers,
+    pub ip: usize,
+    pub ext: E,
+}
+
+impl<'a, E: Extension> Machine<'a, E> {
+    pub fn new(program: &'a [Inst], ext: E) -> Machine<'a, E> {
+        Machine {
+            program,
+            regs: Registers::new(),
+            ip: 0,
+            ext,
+        }
+    }
+
+    /// Whether the instruction pointer left the program.
+    pub fn is_done(&self) -> bool {
+        self.ip >= self.program.len()
+    }
+
+    /// Moves the instruction pointer by `offset`. Jumping before the start
+    /// ends the program, just like jumping past the end.
+    fn jump(&mut self, offset: i64) {
+        let target = self.ip as i64 + offset;
+        self.ip = if target < 0 {
+            self.program.len()
+        } else {
+            target as usize
+        };
+    }
+
+    /// Runs one instruction, returning false if the program is done or the
+    /// extension halted it.
+    pub fn step(&mut self) -> bool {
+        use self::Inst::*;
+
+        let inst = match self.program.get(self.ip) {
+            Some(&inst) => inst,
+            None => return false,
+        };
+
+        self.ext.before(&inst);
+        match inst {
+            Set(reg, arg) => self.regs[reg] = arg.eval(&self.regs),
+            Add(reg, arg) => self.regs[reg] += arg.eval(&self.regs),
+            Sub(reg, arg) => self.regs[reg] -= arg.eval(&self.regs),
+            Mul(reg, arg) => self.regs[reg] *= arg.eval(&self.regs),
+            Mod(reg, arg) => self.regs[reg] %= arg.eval(&self.regs),
+            Jgz(cond, offset) | Jnz(cond, offset) => {
+                let cond = cond.eval(&self.regs);
+                let taken = match inst {
+                    Jgz(..) => cond > 0,
+                    _ => cond != 0,
+                };
+                if taken {
+                    let offset = offset.eval(&self.regs);
+                    self.jump(offset);
+                    return true;
+                }
+            }
+            Snd(arg) => {
+                let value = arg.eval(&self.regs);
+                self.ext.send(value);
+            }
+            Rcv(reg) => match self.ext.receive(self.regs[reg]) {
+                Receive::Store(value) => self.regs[reg] = value,
+                Receive::Skip => (),
+                Receive::Halt => return false,
+            },
+        }
+
+        self.ip += 1;
+        true
+    }
+
+    /// Runs until the program is done or the extension halts it.
+    pub fn run(&mut self) {
+        while self.step() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: &[&str] = &[
+        "set", "add", "sub", "mul", "mod", "jgz", "jnz", "snd", "rcv",
+    ];
+
+    #[test]
+    fn parsing() {
+        let program = parse("set a -3\n\njgz a b  # back\n# snd 1\nsnd 7", ALL).unwrap();
+        assert_eq!(
+            program,
+            vec![
+                Inst::Set(Reg(b'a'), Operand::Val(-3)),
+                Inst::Jgz(Operand::Reg(b'a'), Operand::Reg(b'b')),
+                Inst::Snd(Operand::Val(7)),
+            ]
+        );
+
+        let err = |s: &str| parse(s, &["set", "jnz"]).unwrap_err().to_string();
+        assert_eq!(
+            err("set a 1\nadd a 1"),
+            "line 2: add is not one of the instructions set, jnz"
+        );
+        assert_eq!(err("set 1 a"), "line 1: set needs a register, not 1");
+        assert_eq!(err("jnz a"), "line 1: jnz takes 2 operands, not 1");
+        assert_eq!(
+            parse("nop a", &["nop"]).unwrap_err().to_string(),
+            "line 1: unknown instruction: nop"
+        );
+    }
+
+    #[test]
+    fn formatting() {
+        let source =
+            "set a -3\nadd b a\nsub c 1\nmul d d\nmod e 7\njgz 1 -2\njnz a b\nsnd a\nrcv z";
+        let program = parse(source, ALL).unwrap();
+        let printed: Vec<String> = program.iter().map(Inst::to_string).collect();
+        assert_eq!(printed.join("\n"), source);
+        assert_eq!(program[5].jump_offset(), Some(Operand::Val(-2)));
+        assert_eq!(program[7].jump_offset(), None);
+    }
+
+    #[test]
+    fn arithmetic_and_jumps() {
+        let program = parse(
+            "set a 5\nset b 0\nadd b a\nsub a 1\njnz a -2\nmod b 4\njgz 1 -9",
+            ALL,
+        )
+        .unwrap();
+        let mut machine = Machine::new(&program, ());
+        machine.run();
+        assert!(machine.is_done());
+        assert_eq!((machine.regs['a'], machine.regs['b']), (0, 15 % 4));
+    }
+
+    /// Counts the instructions, and receives the values it was sent.
+    #[derive(Default)]
+    struct Echo {
+        executed: usize,
+        queue: Vec<i64>,
+    }
+
+    impl Extension for Echo {
+        fn before(&mut self, _inst: &Inst) {
+            self.executed += 1;
+        }
+
+        fn send(&mut self, value: i64) {
+            self.queue.push(value);
+        }
+
+        fn receive(&mut self, _value: i64) -> Receive {
+            match self.queue.pop() {
+                Some(value) => Receive::Store(value),
+                None => Receive::Halt,
+            }
+        }
+    }
+
+    #[test]
+    fn extensions() {
+        let program = parse("snd 4\nrcv a\nrcv b\nset c 1", ALL).unwrap();
+        let mut machine = Machine::new(&program, Echo::default());
+        machine.run();
+        assert!(!machine.is_done());
+        assert_eq!((machine.ip, machine.regs['a']), (2, 4));
+        assert_eq!(machine.ext.executed, 3);
+
+        machine.ext.queue.push(9);
+        machine.run();
+        assert!(machine.is_done());
+        assert_eq!((machine.regs['b'], machine.regs['c']), (9, 1));
+    }
+}